@@ -1,18 +1,22 @@
-use std::{
-    future::{ready, Ready},
-    ops::Deref,
-};
+use std::future::{ready, Ready};
 
 use actix_web::{
     dev::Payload,
-    http::header::{self, ContentType},
-    FromRequest, HttpRequest, HttpResponse, ResponseError,
+    http::header::{self, ContentType, HeaderValue},
+    FromRequest, HttpMessage, HttpRequest, HttpResponse, ResponseError,
 };
-use base64::prelude::*;
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Serialize};
 use thiserror::Error;
 
-use crate::X_USER_INFO_HEADER;
+use crate::{
+    encode_user_info, ApisixConsumer, DecodeError, JwtDecodeError, MaybeApisixConsumer,
+    MaybeXUserInfo, XAccessToken, XIdToken, XUserInfo, XUserInfoConfig, X_ACCESS_TOKEN_HEADER,
+    X_CONSUMER_CUSTOM_ID_HEADER, X_CONSUMER_USERNAME_HEADER, X_CREDENTIAL_IDENTIFIER_HEADER,
+    X_ID_TOKEN_HEADER,
+};
+
+#[cfg(feature = "jwt")]
+use crate::{JwtError, JwtVerifier, VerifiedAccessToken};
 
 #[derive(Error, Debug)]
 pub enum XUserInfoError {
@@ -29,6 +33,15 @@ pub enum XUserInfoError {
     JsonDecodeError(#[from] serde_json::Error),
 }
 
+impl From<DecodeError> for XUserInfoError {
+    fn from(err: DecodeError) -> Self {
+        match err {
+            DecodeError::Base64(err) => Self::Base64DecodeError(err),
+            DecodeError::Json(err) => Self::JsonDecodeError(err),
+        }
+    }
+}
+
 impl ResponseError for XUserInfoError {
     fn error_response(&self) -> HttpResponse {
         HttpResponse::build(self.status_code())
@@ -41,28 +54,122 @@ impl ResponseError for XUserInfoError {
     }
 }
 
-#[derive(Debug)]
-pub struct XUserInfo<T>(T)
+impl<T> FromRequest for XUserInfo<T>
 where
-    T: DeserializeOwned;
+    T: DeserializeOwned + 'static,
+{
+    type Error = actix_web::Error;
+
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(extract_user_info(req).map_err(|err| XUserInfoConfig::from_req(req).error(err, req)))
+    }
+}
 
-impl<T> Deref for XUserInfo<T>
+fn extract_user_info<T>(req: &HttpRequest) -> Result<XUserInfo<T>, XUserInfoError>
 where
-    T: DeserializeOwned,
+    T: DeserializeOwned + 'static,
 {
-    type Target = T;
+    if let Some(user_info) = req.extensions_mut().remove::<XUserInfo<T>>() {
+        return Ok(user_info);
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    req.try_into()
+}
+
+impl<T> XUserInfo<T>
+where
+    T: DeserializeOwned + Serialize,
+{
+    /// Encodes `value` as an `x-userinfo` header value that [`XUserInfo::decode`] reads back.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` cannot be serialized to JSON, e.g. a map with non-string keys; use
+    /// [`encode_user_info`] to handle that case.
+    pub fn encode(value: &T) -> HeaderValue {
+        let encoded = encode_user_info(value).expect("x-userinfo must serialize to json");
+        HeaderValue::from_str(&encoded).expect("base64 is a valid header value")
     }
 }
 
-impl<T> FromRequest for XUserInfo<T>
+impl<T> TryFrom<&HttpRequest> for XUserInfo<T>
 where
     T: DeserializeOwned,
 {
     type Error = XUserInfoError;
 
+    fn try_from(req: &HttpRequest) -> Result<Self, Self::Error> {
+        let config = XUserInfoConfig::from_req(req);
+        let header = req
+            .headers()
+            .get(config.get_header_name())
+            .ok_or(XUserInfoError::MissingHeader)?
+            .to_str()?;
+
+        Ok(XUserInfo::decode_with(header, config.get_alphabets())?)
+    }
+}
+
+impl<T> FromRequest for MaybeXUserInfo<T>
+where
+    T: DeserializeOwned + 'static,
+{
+    type Error = actix_web::Error;
+
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(match extract_user_info(req) {
+            Ok(user_info) => Ok(Some(user_info).into()),
+            Err(XUserInfoError::MissingHeader) => Ok(None.into()),
+            Err(err) => Err(XUserInfoConfig::from_req(req).error(err, req)),
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum XTokenError {
+    #[error("{0} header is missing")]
+    MissingHeader(&'static str),
+
+    #[error("invalid {0} header: {1}")]
+    ToStringError(&'static str, header::ToStrError),
+
+    #[error("invalid {0}, {1}")]
+    DecodeError(&'static str, DecodeError),
+
+    #[error("invalid {0}, {1}")]
+    JwtDecodeError(&'static str, JwtDecodeError),
+}
+
+impl ResponseError for XTokenError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::plaintext())
+            .body(self.to_string())
+    }
+
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        actix_web::http::StatusCode::BAD_REQUEST
+    }
+}
+
+fn token_header<'a>(req: &'a HttpRequest, name: &'static str) -> Result<&'a str, XTokenError> {
+    req.headers()
+        .get(name)
+        .ok_or(XTokenError::MissingHeader(name))?
+        .to_str()
+        .map_err(|err| XTokenError::ToStringError(name, err))
+}
+
+impl<T> FromRequest for XIdToken<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = XTokenError;
+
     type Future = Ready<Result<Self, Self::Error>>;
 
     fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
@@ -70,22 +177,191 @@ where
     }
 }
 
-impl<T> TryFrom<&HttpRequest> for XUserInfo<T>
+impl<T> TryFrom<&HttpRequest> for XIdToken<T>
 where
     T: DeserializeOwned,
 {
-    type Error = XUserInfoError;
+    type Error = XTokenError;
 
     fn try_from(req: &HttpRequest) -> Result<Self, Self::Error> {
-        let header = req
-            .headers()
-            .get(X_USER_INFO_HEADER)
-            .ok_or(XUserInfoError::MissingHeader)?
-            .to_str()?;
+        let header = token_header(req, X_ID_TOKEN_HEADER)?;
+
+        XIdToken::decode(header).map_err(|err| XTokenError::DecodeError(X_ID_TOKEN_HEADER, err))
+    }
+}
+
+impl<T> FromRequest for XAccessToken<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = XTokenError;
+
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(req.try_into())
+    }
+}
+
+impl<T> TryFrom<&HttpRequest> for XAccessToken<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = XTokenError;
+
+    fn try_from(req: &HttpRequest) -> Result<Self, Self::Error> {
+        let header = token_header(req, X_ACCESS_TOKEN_HEADER)?;
+
+        XAccessToken::decode(header)
+            .map_err(|err| XTokenError::JwtDecodeError(X_ACCESS_TOKEN_HEADER, err))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ApisixConsumerError {
+    #[error("x-consumer-username header is missing")]
+    MissingHeader,
+
+    #[error("invalid {0} header: {1}")]
+    ToStringError(&'static str, header::ToStrError),
+}
+
+impl ResponseError for ApisixConsumerError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::plaintext())
+            .body(self.to_string())
+    }
 
-        let base64_decoded = BASE64_STANDARD.decode(header)?;
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        actix_web::http::StatusCode::BAD_REQUEST
+    }
+}
+
+fn consumer_header(
+    req: &HttpRequest,
+    name: &'static str,
+) -> Result<Option<String>, ApisixConsumerError> {
+    req.headers()
+        .get(name)
+        .map(|value| {
+            value
+                .to_str()
+                .map(str::to_owned)
+                .map_err(|err| ApisixConsumerError::ToStringError(name, err))
+        })
+        .transpose()
+}
+
+impl FromRequest for ApisixConsumer {
+    type Error = ApisixConsumerError;
+
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(req.try_into())
+    }
+}
+
+impl TryFrom<&HttpRequest> for ApisixConsumer {
+    type Error = ApisixConsumerError;
+
+    fn try_from(req: &HttpRequest) -> Result<Self, Self::Error> {
+        MaybeApisixConsumer::try_from(req)?
+            .into_inner()
+            .ok_or(ApisixConsumerError::MissingHeader)
+    }
+}
+
+impl FromRequest for MaybeApisixConsumer {
+    type Error = ApisixConsumerError;
+
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(req.try_into())
+    }
+}
+
+impl TryFrom<&HttpRequest> for MaybeApisixConsumer {
+    type Error = ApisixConsumerError;
+
+    fn try_from(req: &HttpRequest) -> Result<Self, Self::Error> {
+        let Some(username) = consumer_header(req, X_CONSUMER_USERNAME_HEADER)? else {
+            return Ok(MaybeApisixConsumer(None));
+        };
+
+        Ok(MaybeApisixConsumer(Some(ApisixConsumer {
+            username,
+            credential_identifier: consumer_header(req, X_CREDENTIAL_IDENTIFIER_HEADER)?,
+            custom_id: consumer_header(req, X_CONSUMER_CUSTOM_ID_HEADER)?,
+        })))
+    }
+}
+
+#[cfg(feature = "jwt")]
+#[derive(Error, Debug)]
+pub enum VerifiedTokenError {
+    #[error("jwt verifier is not configured")]
+    MissingVerifier,
+
+    #[error(transparent)]
+    Header(#[from] XTokenError),
+
+    #[error("invalid x-access-token, {0}")]
+    Invalid(#[from] JwtError),
+}
+
+#[cfg(feature = "jwt")]
+impl ResponseError for VerifiedTokenError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::plaintext())
+            .body(self.to_string())
+    }
 
-        Ok(XUserInfo(serde_json::from_slice(&base64_decoded)?))
+    fn status_code(&self) -> actix_web::http::StatusCode {
+        match self {
+            VerifiedTokenError::MissingVerifier => {
+                actix_web::http::StatusCode::INTERNAL_SERVER_ERROR
+            }
+            _ => actix_web::http::StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+#[cfg(feature = "jwt")]
+impl<T> FromRequest for VerifiedAccessToken<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = VerifiedTokenError;
+
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(req.try_into())
+    }
+}
+
+#[cfg(feature = "jwt")]
+impl<T> TryFrom<&HttpRequest> for VerifiedAccessToken<T>
+where
+    T: DeserializeOwned,
+{
+    type Error = VerifiedTokenError;
+
+    fn try_from(req: &HttpRequest) -> Result<Self, Self::Error> {
+        let verifier = req
+            .app_data::<JwtVerifier>()
+            .or_else(|| {
+                req.app_data::<actix_web::web::Data<JwtVerifier>>()
+                    .map(|data| data.as_ref())
+            })
+            .ok_or(VerifiedTokenError::MissingVerifier)?;
+        let header = token_header(req, X_ACCESS_TOKEN_HEADER)?;
+
+        Ok(VerifiedAccessToken::verify(verifier, header)?)
     }
 }
 
@@ -95,8 +371,9 @@ mod tests {
 
     use super::*;
     use actix_web::test::TestRequest;
+    use base64::prelude::*;
     use serde::{Deserialize, Serialize};
-    use serde_json::json;
+    use serde_json::{json, Value};
 
     #[derive(Deserialize, Debug, PartialEq, Serialize)]
     #[serde(rename_all = "snake_case")]
@@ -126,4 +403,143 @@ mod tests {
         assert_eq!(x_user_info.0.name, "test name");
         assert_eq!(x_user_info.0.iat, 1516239022);
     }
+
+    #[test]
+    fn test_encode() {
+        let user_info = CustomXUserInfo {
+            sub: "test sub".into(),
+            name: "test name".into(),
+            iat: 1516239022,
+        };
+
+        let header = XUserInfo::encode(&user_info);
+        let decoded: Value =
+            serde_json::from_slice(&BASE64_STANDARD.decode(header.as_bytes()).unwrap()).unwrap();
+        assert_eq!(
+            decoded,
+            json!({"sub": "test sub", "name": "test name", "iat": 1516239022})
+        );
+        assert_eq!(
+            XUserInfo::<CustomXUserInfo>::decode(header.as_bytes())
+                .unwrap()
+                .into_inner(),
+            user_info
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_maybe_x_user_info() {
+        let header_raw = json!({"sub": "test sub", "name": "test name", "iat": 1516239022});
+
+        let req = TestRequest::default()
+            .append_header((
+                X_USER_INFO_HEADER,
+                BASE64_STANDARD.encode(header_raw.to_string()),
+            ))
+            .to_http_request();
+        let maybe = MaybeXUserInfo::<CustomXUserInfo>::extract(&req)
+            .await
+            .unwrap();
+        assert_eq!(maybe.as_ref().unwrap().sub, "test sub");
+
+        let req = TestRequest::default().to_http_request();
+        let maybe = MaybeXUserInfo::<CustomXUserInfo>::extract(&req)
+            .await
+            .unwrap();
+        assert!(maybe.is_none());
+
+        let req = TestRequest::default()
+            .append_header((X_USER_INFO_HEADER, "not base64!"))
+            .to_http_request();
+        let err = MaybeXUserInfo::<CustomXUserInfo>::extract(&req)
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid x-userinfo, base64 decode error"));
+
+        let req = TestRequest::default()
+            .append_header((X_USER_INFO_HEADER, BASE64_STANDARD.encode("{}")))
+            .to_http_request();
+        let err = MaybeXUserInfo::<CustomXUserInfo>::extract(&req)
+            .await
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("invalid x-userinfo, json decode error"));
+    }
+
+    #[actix_rt::test]
+    async fn test_x_tokens() {
+        let claims = json!({"sub": "test sub", "name": "test name", "iat": 1516239022});
+        let access_token = format!(
+            "eyJhbGciOiJSUzI1NiJ9.{}.c2lnbmF0dXJl",
+            BASE64_URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+
+        let req = TestRequest::default()
+            .append_header((X_ACCESS_TOKEN_HEADER, access_token.as_str()))
+            .append_header((
+                X_ID_TOKEN_HEADER,
+                BASE64_STANDARD.encode(claims.to_string()),
+            ))
+            .to_http_request();
+        let mut payload = Payload::None;
+
+        let access: XAccessToken<CustomXUserInfo> = XAccessToken::from_request(&req, &mut payload)
+            .await
+            .unwrap();
+        assert_eq!(access.token(), access_token);
+        assert_eq!(access.sub, "test sub");
+
+        let id_token: XIdToken<CustomXUserInfo> =
+            XIdToken::from_request(&req, &mut payload).await.unwrap();
+        assert_eq!(id_token.name, "test name");
+
+        let req = TestRequest::default()
+            .append_header((X_ACCESS_TOKEN_HEADER, "garbage"))
+            .to_http_request();
+        let err = XAccessToken::<CustomXUserInfo>::from_request(&req, &mut payload)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            XTokenError::JwtDecodeError(X_ACCESS_TOKEN_HEADER, JwtDecodeError::Malformed)
+        ));
+
+        let err = XIdToken::<CustomXUserInfo>::from_request(&req, &mut payload)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "x-id-token header is missing");
+    }
+
+    #[actix_rt::test]
+    async fn test_apisix_consumer() {
+        let req = TestRequest::default()
+            .append_header((X_CONSUMER_USERNAME_HEADER, "partner_acme"))
+            .append_header((X_CREDENTIAL_IDENTIFIER_HEADER, "cred-1"))
+            .to_http_request();
+        let consumer = ApisixConsumer::extract(&req).await.unwrap();
+        assert_eq!(consumer.username, "partner_acme");
+        assert_eq!(consumer.credential_identifier.as_deref(), Some("cred-1"));
+        assert_eq!(consumer.custom_id, None);
+
+        let req = TestRequest::default().to_http_request();
+        let err = ApisixConsumer::extract(&req).await.unwrap_err();
+        assert!(matches!(err, ApisixConsumerError::MissingHeader));
+        let maybe = MaybeApisixConsumer::extract(&req).await.unwrap();
+        assert!(maybe.is_none());
+
+        let req = TestRequest::default()
+            .append_header((
+                X_CONSUMER_USERNAME_HEADER,
+                header::HeaderValue::from_bytes(b"\xff").unwrap(),
+            ))
+            .to_http_request();
+        let err = MaybeApisixConsumer::extract(&req).await.unwrap_err();
+        assert!(matches!(
+            err,
+            ApisixConsumerError::ToStringError(X_CONSUMER_USERNAME_HEADER, _)
+        ));
+    }
 }
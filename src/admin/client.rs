@@ -0,0 +1,742 @@
+use std::{collections::BTreeSet, marker::PhantomData};
+
+use reqwest::{Method, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
+use thiserror::Error;
+
+use super::{
+    Consumer, ConsumerGroup, Credential, GlobalRule, PluginConfig, PluginMetadata, Route,
+    SecretManager, Service, Ssl, StreamRoute, Subsystem, Upstream,
+};
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+const ADMIN_PREFIX: &str = "apisix/admin";
+
+#[derive(Error, Debug)]
+pub enum AdminError {
+    #[error("admin api request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("admin api returned {status}: {message}")]
+    Api { status: u16, message: String },
+
+    #[error("plugins not enabled on the gateway: {}", .0.join(", "))]
+    PluginsNotEnabled(Vec<String>),
+}
+
+/// A single resource in the v3 `{key, value, createdIndex, modifiedIndex}` envelope.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Item<R> {
+    pub key: String,
+    pub value: R,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_index: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_index: Option<u64>,
+}
+
+impl<R> Item<R> {
+    /// The resource id, i.e. the last segment of `key`.
+    pub fn id(&self) -> &str {
+        self.key.rsplit('/').next().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct List<R> {
+    pub total: u64,
+    #[serde(
+        deserialize_with = "deserialize_list",
+        bound(deserialize = "R: Deserialize<'de>")
+    )]
+    pub list: Vec<Item<R>>,
+}
+
+/// APISIX encodes an empty list as `{}`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ListOrEmpty<T> {
+    List(Vec<T>),
+    Empty {},
+}
+
+impl<T> From<ListOrEmpty<T>> for Vec<T> {
+    fn from(list: ListOrEmpty<T>) -> Self {
+        match list {
+            ListOrEmpty::List(list) => list,
+            ListOrEmpty::Empty {} => Vec::new(),
+        }
+    }
+}
+
+pub(crate) fn deserialize_list<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(ListOrEmpty::deserialize(deserializer)?.into())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ListParams {
+    pub page: u32,
+    pub page_size: u32,
+}
+
+impl Default for ListParams {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            page_size: 500,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    error_msg: Option<String>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AdminClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl AdminClient {
+    /// Creates a client for an Admin API listening on `base_url`, e.g. `http://127.0.0.1:9180`.
+    pub fn new(base_url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self::with_client(reqwest::Client::new(), base_url, api_key)
+    }
+
+    pub fn with_client(
+        http: reqwest::Client,
+        base_url: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_owned();
+
+        Self {
+            http,
+            base_url,
+            api_key: api_key.into(),
+        }
+    }
+
+    pub fn routes(&self) -> Api<'_, Route> {
+        self.api("routes")
+    }
+
+    pub fn services(&self) -> Api<'_, Service> {
+        self.api("services")
+    }
+
+    pub fn upstreams(&self) -> Api<'_, Upstream> {
+        self.api("upstreams")
+    }
+
+    pub fn consumers(&self) -> Api<'_, Consumer> {
+        self.api("consumers")
+    }
+
+    pub fn consumer_groups(&self) -> Api<'_, ConsumerGroup> {
+        self.api("consumer_groups")
+    }
+
+    pub fn credentials(&self, username: &str) -> Api<'_, Credential> {
+        self.api(format!("consumers/{username}/credentials"))
+    }
+
+    pub fn stream_routes(&self) -> Api<'_, StreamRoute> {
+        self.api("stream_routes")
+    }
+
+    pub fn ssls(&self) -> Api<'_, Ssl> {
+        self.api("ssls")
+    }
+
+    pub fn global_rules(&self) -> Api<'_, GlobalRule> {
+        self.api("global_rules")
+    }
+
+    pub fn plugin_configs(&self) -> Api<'_, PluginConfig> {
+        self.api("plugin_configs")
+    }
+
+    /// Secrets of one manager, e.g. `client.secrets::<VaultSecret>().put("1", &secret)`.
+    pub fn secrets<R>(&self) -> Api<'_, R>
+    where
+        R: SecretManager,
+    {
+        self.api(format!("secrets/{}", R::MANAGER))
+    }
+
+    /// Raw access to `/plugin_metadata/{plugin name}`.
+    pub fn plugin_metadata<R>(&self) -> Api<'_, R> {
+        self.api("plugin_metadata")
+    }
+
+    pub async fn get_plugin_metadata<M>(&self) -> Result<M, AdminError>
+    where
+        M: PluginMetadata,
+    {
+        Ok(self.plugin_metadata().get(M::NAME).await?.value)
+    }
+
+    pub async fn put_plugin_metadata<M>(&self, metadata: &M) -> Result<M, AdminError>
+    where
+        M: PluginMetadata,
+    {
+        Ok(self.plugin_metadata().put(M::NAME, metadata).await?.value)
+    }
+
+    pub async fn delete_plugin_metadata<M>(&self) -> Result<(), AdminError>
+    where
+        M: PluginMetadata,
+    {
+        self.plugin_metadata::<M>().delete(M::NAME).await
+    }
+
+    /// Names of the plugins enabled on the gateway.
+    pub async fn list_plugins(&self, subsystem: Subsystem) -> Result<Vec<String>, AdminError> {
+        let request = self
+            .request(Method::GET, "plugins/list")
+            .query(&[("subsystem", subsystem)]);
+        self.send(request).await
+    }
+
+    pub async fn plugin_schema(
+        &self,
+        name: &str,
+        subsystem: Subsystem,
+    ) -> Result<serde_json::Value, AdminError> {
+        let request = self
+            .request(Method::GET, &format!("plugins/{name}"))
+            .query(&[("subsystem", subsystem)]);
+        self.send(request).await
+    }
+
+    /// Returns the plugins of `required` that are not enabled on the gateway.
+    pub async fn missing_plugins<I, S>(
+        &self,
+        subsystem: Subsystem,
+        required: I,
+    ) -> Result<Vec<String>, AdminError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let enabled: BTreeSet<_> = self.list_plugins(subsystem).await?.into_iter().collect();
+        let missing: BTreeSet<_> = required
+            .into_iter()
+            .filter(|name| !enabled.contains(name.as_ref()))
+            .map(|name| name.as_ref().to_owned())
+            .collect();
+        Ok(missing.into_iter().collect())
+    }
+
+    /// Fails with [`AdminError::PluginsNotEnabled`] unless every plugin of `required` is enabled,
+    /// e.g. at startup with the plugin names of the routes a service relies on.
+    pub async fn ensure_plugins_enabled<I, S>(
+        &self,
+        subsystem: Subsystem,
+        required: I,
+    ) -> Result<(), AdminError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let missing = self.missing_plugins(subsystem, required).await?;
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(AdminError::PluginsNotEnabled(missing))
+        }
+    }
+
+    /// Typed access to any resource collection below `/apisix/admin`.
+    pub fn api<R>(&self, path: impl Into<String>) -> Api<'_, R> {
+        Api {
+            client: self,
+            path: path.into(),
+            _resource: PhantomData,
+        }
+    }
+
+    pub(crate) fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http
+            .request(method, format!("{}/{ADMIN_PREFIX}/{path}", self.base_url))
+            .header(API_KEY_HEADER, &self.api_key)
+    }
+
+    pub(crate) async fn send<T>(&self, request: RequestBuilder) -> Result<T, AdminError>
+    where
+        T: DeserializeOwned,
+    {
+        Ok(check(request.send().await?).await?.json().await?)
+    }
+}
+
+pub(crate) async fn check(response: Response) -> Result<Response, AdminError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    Err(AdminError::Api {
+        status: status.as_u16(),
+        message: error_message(response).await?,
+    })
+}
+
+/// The `error_msg` or `message` of an error body, or the raw body if it has neither.
+pub(crate) async fn error_message(response: Response) -> Result<String, reqwest::Error> {
+    let body = response.text().await?;
+    Ok(serde_json::from_str::<ErrorBody>(&body)
+        .ok()
+        .and_then(|err| err.error_msg.or(err.message))
+        .unwrap_or(body))
+}
+
+/// CRUD operations on one Admin API resource collection.
+pub struct Api<'a, R> {
+    client: &'a AdminClient,
+    path: String,
+    _resource: PhantomData<fn() -> R>,
+}
+
+impl<R> Api<'_, R>
+where
+    R: Serialize + DeserializeOwned,
+{
+    pub async fn list(&self, params: ListParams) -> Result<List<R>, AdminError> {
+        let request = self.client.request(Method::GET, &self.path).query(&params);
+        self.client.send(request).await
+    }
+
+    /// Fetches every page of the collection.
+    pub async fn list_all(&self) -> Result<Vec<Item<R>>, AdminError> {
+        let mut params = ListParams::default();
+        let mut items = Vec::new();
+        loop {
+            let page = self.list(params).await?;
+            let fetched = page.list.len();
+            items.extend(page.list);
+            if fetched < params.page_size as usize || items.len() as u64 >= page.total {
+                return Ok(items);
+            }
+            params.page += 1;
+        }
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Item<R>, AdminError> {
+        let request = self.client.request(Method::GET, &self.item_path(id));
+        self.client.send(request).await
+    }
+
+    pub async fn put(&self, id: &str, resource: &R) -> Result<Item<R>, AdminError> {
+        let request = self
+            .client
+            .request(Method::PUT, &self.item_path(id))
+            .json(resource);
+        self.client.send(request).await
+    }
+
+    /// Creates a resource with a server generated id.
+    pub async fn post(&self, resource: &R) -> Result<Item<R>, AdminError> {
+        let request = self.client.request(Method::POST, &self.path).json(resource);
+        self.client.send(request).await
+    }
+
+    /// Merges `patch` into the stored resource.
+    pub async fn patch<P>(&self, id: &str, patch: &P) -> Result<Item<R>, AdminError>
+    where
+        P: Serialize + ?Sized,
+    {
+        let request = self
+            .client
+            .request(Method::PATCH, &self.item_path(id))
+            .json(patch);
+        self.client.send(request).await
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<(), AdminError> {
+        let request = self.client.request(Method::DELETE, &self.item_path(id));
+        check(request.send().await?).await?;
+        Ok(())
+    }
+
+    fn item_path(&self, id: &str) -> String {
+        format!("{}/{id}", self.path)
+    }
+}
+
+impl Api<'_, Consumer> {
+    /// Creates or updates a consumer; consumers are keyed by `username` in the body.
+    pub async fn upsert(&self, consumer: &Consumer) -> Result<Item<Consumer>, AdminError> {
+        let request = self.client.request(Method::PUT, &self.path).json(consumer);
+        self.client.send(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::{HttpLoggerMetadata, KeyAuth, Nodes, Plugins, Upstream, VaultSecret};
+    use serde_json::json;
+    use wiremock::{
+        matchers::{body_json, header, method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn route_item(id: &str) -> serde_json::Value {
+        json!({
+            "key": format!("/apisix/routes/{id}"),
+            "value": {"id": id, "uri": format!("/{id}"), "upstream_id": "1"},
+            "createdIndex": 10,
+            "modifiedIndex": 12
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/apisix/admin/routes"))
+            .and(header(API_KEY_HEADER, "secret"))
+            .and(query_param("page", "1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"total": 2, "list": [route_item("a"), route_item("b")]})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/apisix/admin/upstreams"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"total": 0, "list": {}})))
+            .mount(&server)
+            .await;
+
+        let client = AdminClient::new(format!("{}/", server.uri()), "secret");
+
+        let routes = client.routes().list_all().await.unwrap();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[1].id(), "b");
+        assert_eq!(routes[1].value.uri.as_deref(), Some("/b"));
+        assert_eq!(routes[1].created_index, Some(10));
+
+        let upstreams = client
+            .upstreams()
+            .list(ListParams::default())
+            .await
+            .unwrap();
+        assert_eq!(upstreams.total, 0);
+        assert!(upstreams.list.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_all_pages() {
+        let server = MockServer::start().await;
+        let page = |page: &str, ids: std::ops::Range<u32>| {
+            let list: Vec<_> = ids.map(|id| route_item(&id.to_string())).collect();
+            Mock::given(method("GET"))
+                .and(path("/apisix/admin/routes"))
+                .and(query_param("page", page))
+                .and(query_param("page_size", "500"))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(json!({"total": 501, "list": list})),
+                )
+        };
+        page("1", 0..500).mount(&server).await;
+        page("2", 500..501).mount(&server).await;
+
+        let client = AdminClient::new(server.uri(), "secret");
+        let routes = client.routes().list_all().await.unwrap();
+        assert_eq!(routes.len(), 501);
+        assert_eq!(routes[500].id(), "500");
+    }
+
+    #[tokio::test]
+    async fn test_crud() {
+        let server = MockServer::start().await;
+        let upstream = Upstream {
+            nodes: Some(Nodes::Map([("10.0.0.1:80".to_owned(), 1)].into())),
+            ..Default::default()
+        };
+        let stored = json!({
+            "key": "/apisix/upstreams/1",
+            "value": {"id": "1", "nodes": {"10.0.0.1:80": 1}, "create_time": 1700000000}
+        });
+
+        Mock::given(method("PUT"))
+            .and(path("/apisix/admin/upstreams/1"))
+            .and(header(API_KEY_HEADER, "secret"))
+            .and(body_json(json!({"nodes": {"10.0.0.1:80": 1}})))
+            .respond_with(ResponseTemplate::new(201).set_body_json(&stored))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/apisix/admin/upstreams"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(&stored))
+            .mount(&server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/apisix/admin/upstreams/1"))
+            .and(body_json(json!({"retries": 3})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&stored))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/apisix/admin/upstreams/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&stored))
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/apisix/admin/upstreams/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"deleted": "1"})))
+            .mount(&server)
+            .await;
+
+        let client = AdminClient::new(server.uri(), "secret");
+        let api = client.upstreams();
+
+        let item = api.put("1", &upstream).await.unwrap();
+        assert_eq!(item.id(), "1");
+        assert_eq!(item.value.create_time, Some(1700000000));
+        assert_eq!(api.post(&upstream).await.unwrap().id(), "1");
+        api.patch("1", &json!({"retries": 3})).await.unwrap();
+        assert_eq!(api.get("1").await.unwrap().value.id.as_deref(), Some("1"));
+        api.delete("1").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_plugin_metadata() {
+        let server = MockServer::start().await;
+        let stored = json!({
+            "key": "/apisix/plugin_metadata/http-logger",
+            "value": {"id": "http-logger", "log_format": {"host": "$host"}}
+        });
+        Mock::given(method("PUT"))
+            .and(path("/apisix/admin/plugin_metadata/http-logger"))
+            .and(body_json(json!({"log_format": {"host": "$host"}})))
+            .respond_with(ResponseTemplate::new(201).set_body_json(&stored))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/apisix/admin/plugin_metadata/http-logger"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&stored))
+            .mount(&server)
+            .await;
+
+        let client = AdminClient::new(server.uri(), "secret");
+        let metadata = HttpLoggerMetadata {
+            log_format: [("host".to_owned(), json!("$host"))].into_iter().collect(),
+            ..Default::default()
+        };
+
+        client.put_plugin_metadata(&metadata).await.unwrap();
+        let stored: HttpLoggerMetadata = client.get_plugin_metadata().await.unwrap();
+        assert_eq!(stored.log_format["host"], "$host");
+        assert_eq!(stored.id.as_deref(), Some("http-logger"));
+    }
+
+    #[tokio::test]
+    async fn test_plugins_enabled() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/apisix/admin/plugins/list"))
+            .and(query_param("subsystem", "http"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                "key-auth",
+                "limit-count",
+                "cors"
+            ])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/apisix/admin/plugins/key-auth"))
+            .and(query_param("subsystem", "http"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"type": "object", "properties": {}})),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AdminClient::new(server.uri(), "secret");
+
+        let schema = client
+            .plugin_schema("key-auth", Subsystem::Http)
+            .await
+            .unwrap();
+        assert_eq!(schema["type"], "object");
+
+        client
+            .ensure_plugins_enabled(Subsystem::Http, ["cors", "key-auth"])
+            .await
+            .unwrap();
+
+        let route: Route = serde_json::from_value(json!({
+            "uri": "/",
+            "plugins": {"key-auth": {}, "openid-connect": {"client_id": "a", "client_secret": "b", "discovery": "c"}, "ip-restriction": {}}
+        }))
+        .unwrap();
+        let err = client
+            .ensure_plugins_enabled(Subsystem::Http, route.plugins.keys())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "plugins not enabled on the gateway: ip-restriction, openid-connect"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/apisix/admin/routes/missing"))
+            .respond_with(
+                ResponseTemplate::new(404).set_body_json(json!({"message": "Key not found"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/apisix/admin/routes/bad"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(
+                json!({"error_msg": "invalid configuration: property \"uri\" is required"}),
+            ))
+            .mount(&server)
+            .await;
+
+        let client = AdminClient::new(server.uri(), "secret");
+
+        let err = client.routes().get("missing").await.unwrap_err();
+        assert!(
+            matches!(err, AdminError::Api { status: 404, message } if message == "Key not found")
+        );
+
+        let err = client
+            .routes()
+            .put("bad", &Route::default())
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "admin api returned 400: invalid configuration: property \"uri\" is required"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_consumers_and_credentials() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/apisix/admin/consumers"))
+            .and(body_json(
+                json!({"username": "partner_acme", "group_id": "partners"}),
+            ))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "key": "/apisix/consumers/partner_acme",
+                "value": {"username": "partner_acme", "group_id": "partners"}
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path(
+                "/apisix/admin/consumers/partner_acme/credentials/primary",
+            ))
+            .and(body_json(
+                json!({"plugins": {"key-auth": {"key": "rotated"}}}),
+            ))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "key": "/apisix/consumers/partner_acme/credentials/primary",
+                "value": {"id": "primary", "plugins": {"key-auth": {"key": "rotated"}}}
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AdminClient::new(server.uri(), "secret");
+        let consumer = Consumer {
+            username: "partner_acme".into(),
+            group_id: Some("partners".into()),
+            ..Default::default()
+        };
+        let item = client.consumers().upsert(&consumer).await.unwrap();
+        assert_eq!(item.id(), "partner_acme");
+
+        let credential = Credential {
+            plugins: Plugins::new().with(KeyAuth {
+                key: Some("rotated".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let item = client
+            .credentials("partner_acme")
+            .put("primary", &credential)
+            .await
+            .unwrap();
+        assert_eq!(item.id(), "primary");
+        let key_auth = item.value.plugins.get_typed::<KeyAuth>().unwrap().unwrap();
+        assert_eq!(key_auth.key.as_deref(), Some("rotated"));
+    }
+
+    #[tokio::test]
+    async fn test_secrets_and_ssls() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/apisix/admin/secrets/vault/prod"))
+            .and(body_json(json!({
+                "uri": "https://vault.internal:8200",
+                "prefix": "kv/apisix",
+                "token": "hvs.token"
+            })))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({
+                "key": "/apisix/secrets/vault/prod",
+                "value": {
+                    "id": "vault/prod",
+                    "uri": "https://vault.internal:8200",
+                    "prefix": "kv/apisix",
+                    "token": "hvs.token"
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/apisix/admin/ssls/1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "key": "/apisix/ssls/1",
+                "value": {
+                    "id": "1",
+                    "type": "server",
+                    "snis": ["api.example.com"],
+                    "cert": "-----BEGIN CERTIFICATE-----",
+                    "client": {"ca": "-----BEGIN CERTIFICATE-----", "depth": 2}
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let client = AdminClient::new(server.uri(), "secret");
+        let secret = VaultSecret {
+            uri: "https://vault.internal:8200".into(),
+            prefix: "kv/apisix".into(),
+            token: "hvs.token".into(),
+            ..Default::default()
+        };
+        let item = client
+            .secrets::<VaultSecret>()
+            .put("prod", &secret)
+            .await
+            .unwrap();
+        assert_eq!(item.value.id.as_deref(), Some("vault/prod"));
+
+        let ssl = client.ssls().get("1").await.unwrap().value;
+        assert_eq!(ssl.snis.unwrap(), ["api.example.com"]);
+        assert_eq!(ssl.client.unwrap().depth, Some(2));
+    }
+}
@@ -0,0 +1,288 @@
+use reqwest::{Method, Response};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use super::{
+    client::{deserialize_list, error_message, ListOrEmpty},
+    Item, Route, Service, Upstream,
+};
+
+const CONTROL_PREFIX: &str = "v1";
+
+#[derive(Error, Debug)]
+pub enum ControlError {
+    #[error("control api request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("control api returned {status}: {message}")]
+    Api { status: u16, message: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    MostlyHealthy,
+    MostlyUnhealthy,
+    Unhealthy,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthCounter {
+    #[serde(default)]
+    pub success: u64,
+    #[serde(default)]
+    pub http_failure: u64,
+    #[serde(default)]
+    pub tcp_failure: u64,
+    #[serde(default)]
+    pub timeout_failure: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeHealth {
+    pub ip: String,
+    pub port: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    pub status: HealthStatus,
+    #[serde(default)]
+    pub counter: HealthCounter,
+}
+
+/// Health checker state of one upstream, named after the owning resource key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpstreamHealth {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub check_type: String,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub nodes: Vec<NodeHealth>,
+}
+
+/// Client for the Control API, which exposes the runtime state of one APISIX instance.
+#[derive(Debug, Clone)]
+pub struct ControlClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl ControlClient {
+    /// Creates a client for a Control API listening on `base_url`, e.g. `http://127.0.0.1:9090`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self::with_client(reqwest::Client::new(), base_url)
+    }
+
+    pub fn with_client(http: reqwest::Client, base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_owned();
+
+        Self { http, base_url }
+    }
+
+    /// The JSON schemas of core resources and loaded plugins.
+    pub async fn schema(&self) -> Result<Value, ControlError> {
+        self.get("schema").await
+    }
+
+    #[cfg(feature = "validate")]
+    pub async fn schema_validator(&self) -> Result<super::SchemaValidator, ControlSchemaError> {
+        Ok(super::SchemaValidator::from_value(&self.schema().await?)?)
+    }
+
+    pub async fn healthcheck(&self) -> Result<Vec<UpstreamHealth>, ControlError> {
+        let health: ListOrEmpty<UpstreamHealth> = self.get("healthcheck").await?;
+        Ok(health.into())
+    }
+
+    pub async fn upstream_health(&self, id: &str) -> Result<UpstreamHealth, ControlError> {
+        self.get(&format!("healthcheck/upstreams/{id}")).await
+    }
+
+    pub async fn routes(&self) -> Result<Vec<Item<Route>>, ControlError> {
+        self.list("routes").await
+    }
+
+    pub async fn route(&self, id: &str) -> Result<Item<Route>, ControlError> {
+        self.get(&format!("route/{id}")).await
+    }
+
+    pub async fn services(&self) -> Result<Vec<Item<Service>>, ControlError> {
+        self.list("services").await
+    }
+
+    pub async fn service(&self, id: &str) -> Result<Item<Service>, ControlError> {
+        self.get(&format!("service/{id}")).await
+    }
+
+    pub async fn upstreams(&self) -> Result<Vec<Item<Upstream>>, ControlError> {
+        self.list("upstreams").await
+    }
+
+    pub async fn upstream(&self, id: &str) -> Result<Item<Upstream>, ControlError> {
+        self.get(&format!("upstream/{id}")).await
+    }
+
+    /// Asks every worker to reload its plugins from the configuration.
+    pub async fn reload_plugins(&self) -> Result<(), ControlError> {
+        let request = self.request(Method::PUT, "plugins/reload");
+        check(request.send().await?).await?;
+        Ok(())
+    }
+
+    /// The nodes known to a service discovery, e.g. `discovery_dump("eureka")`.
+    pub async fn discovery_dump(&self, discovery: &str) -> Result<Value, ControlError> {
+        self.get(&format!("discovery/{discovery}/dump")).await
+    }
+
+    pub async fn discovery_dump_file(&self, discovery: &str) -> Result<Value, ControlError> {
+        self.get(&format!("discovery/{discovery}/show_dump_file"))
+            .await
+    }
+
+    async fn list<R>(&self, path: &str) -> Result<Vec<Item<R>>, ControlError>
+    where
+        R: DeserializeOwned,
+    {
+        let items: ListOrEmpty<Item<R>> = self.get(path).await?;
+        Ok(items.into())
+    }
+
+    async fn get<T>(&self, path: &str) -> Result<T, ControlError>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self.request(Method::GET, path).send().await?;
+        Ok(check(response).await?.json().await?)
+    }
+
+    fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        self.http
+            .request(method, format!("{}/{CONTROL_PREFIX}/{path}", self.base_url))
+    }
+}
+
+async fn check(response: Response) -> Result<Response, ControlError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    Err(ControlError::Api {
+        status: status.as_u16(),
+        message: error_message(response).await?,
+    })
+}
+
+#[cfg(feature = "validate")]
+#[derive(Error, Debug)]
+pub enum ControlSchemaError {
+    #[error(transparent)]
+    Request(#[from] ControlError),
+
+    #[error(transparent)]
+    Schema(#[from] super::SchemaError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    #[tokio::test]
+    async fn test_healthcheck() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/healthcheck"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "name": "/apisix/upstreams/1",
+                "type": "http",
+                "nodes": [
+                    {
+                        "ip": "10.0.0.1",
+                        "port": 80,
+                        "status": "healthy",
+                        "counter": {"success": 2, "http_failure": 0, "tcp_failure": 0, "timeout_failure": 0}
+                    },
+                    {"ip": "10.0.0.2", "port": 80, "status": "mostly_unhealthy", "counter": {"http_failure": 1}}
+                ]
+            }])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/healthcheck/upstreams/2"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(
+                    json!({"name": "/apisix/upstreams/2", "type": "tcp", "nodes": {}}),
+                ),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/healthcheck/upstreams/3"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_json(json!({"error_msg": "health checker not found"})),
+            )
+            .mount(&server)
+            .await;
+
+        let client = ControlClient::new(server.uri());
+
+        let health = client.healthcheck().await.unwrap();
+        assert_eq!(health[0].nodes[0].status, HealthStatus::Healthy);
+        assert_eq!(health[0].nodes[0].counter.success, 2);
+        assert_eq!(health[0].nodes[1].status, HealthStatus::MostlyUnhealthy);
+
+        let upstream = client.upstream_health("2").await.unwrap();
+        assert_eq!(upstream.check_type, "tcp");
+        assert!(upstream.nodes.is_empty());
+
+        let err = client.upstream_health("3").await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "control api returned 404: health checker not found"
+        );
+        assert!(matches!(err, ControlError::Api { status: 404, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_runtime_config() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/routes"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "key": "/apisix/routes/1",
+                "value": {"id": "1", "uri": "/get", "upstream_id": "1"},
+                "modifiedIndex": 5,
+                "clean_handlers": {},
+                "has_domain": false
+            }])))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/upstreams"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/v1/plugins/reload"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("done"))
+            .mount(&server)
+            .await;
+
+        let client = ControlClient::new(server.uri());
+
+        let routes = client.routes().await.unwrap();
+        assert_eq!(routes[0].id(), "1");
+        assert_eq!(routes[0].value.uri.as_deref(), Some("/get"));
+        assert!(client.upstreams().await.unwrap().is_empty());
+        client.reload_plugins().await.unwrap();
+    }
+}
@@ -0,0 +1,32 @@
+//! Typed models and clients for the APISIX Admin API v3 and Control API.
+
+#[cfg(feature = "admin-client")]
+mod client;
+#[cfg(feature = "admin-client")]
+mod control;
+mod model;
+mod plugins;
+#[cfg(feature = "x509")]
+mod ssl;
+#[cfg(feature = "standalone")]
+mod standalone;
+pub mod sync;
+#[cfg(feature = "validate")]
+mod validate;
+
+#[cfg(feature = "admin-client")]
+pub use client::{AdminClient, AdminError, Api, Item, List, ListParams};
+#[cfg(all(feature = "admin-client", feature = "validate"))]
+pub use control::ControlSchemaError;
+#[cfg(feature = "admin-client")]
+pub use control::{
+    ControlClient, ControlError, HealthCounter, HealthStatus, NodeHealth, UpstreamHealth,
+};
+pub use model::*;
+pub use plugins::*;
+#[cfg(feature = "x509")]
+pub use ssl::{CertificateInfo, SslError};
+#[cfg(feature = "standalone")]
+pub use standalone::{StandaloneConfig, StandaloneError, END_MARKER};
+#[cfg(feature = "validate")]
+pub use validate::{SchemaError, SchemaValidator, ValidationErrors, Violation};
@@ -0,0 +1,731 @@
+use std::{
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use super::Subsystem;
+
+/// Bundled plugins that run in the stream subsystem.
+pub const STREAM_PLUGINS: &[&str] = &[
+    "ip-restriction",
+    "limit-conn",
+    "mqtt-proxy",
+    "prometheus",
+    "syslog",
+];
+
+/// Bundled plugins that only run in the stream subsystem.
+pub const STREAM_ONLY_PLUGINS: &[&str] = &["mqtt-proxy"];
+
+/// A plugin configuration with a known plugin name.
+///
+/// The typed structs below model the commonly used fields and reject any other one, so that a
+/// misspelled field is not sent to APISIX unnoticed. A catalogue plugin whose configuration does
+/// not fit its struct is kept as [`Plugin::Other`] when deserializing [`Plugins`] and listed by
+/// [`Plugins::invalid`].
+pub trait TypedPlugin: Serialize + DeserializeOwned {
+    const NAME: &'static str;
+}
+
+/// Instance-wide metadata of a plugin, stored below `/plugin_metadata/{name}`.
+///
+/// Like [`TypedPlugin`]s the structs reject unmodelled fields; use
+/// `AdminClient::plugin_metadata::<Value>()` for raw access.
+pub trait PluginMetadata: Serialize + DeserializeOwned {
+    const NAME: &'static str;
+}
+
+macro_rules! catalogue {
+    ($($variant:ident => $name:literal,)*) => {
+        /// A plugin configuration, typed for the plugins in the catalogue and raw JSON otherwise.
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum Plugin {
+            $($variant($variant),)*
+            Other(String, Value),
+        }
+
+        impl Plugin {
+            pub fn name(&self) -> &str {
+                match self {
+                    $(Plugin::$variant(_) => $name,)*
+                    Plugin::Other(name, _) => name,
+                }
+            }
+
+            /// Parses `value` into the typed configuration for `name`, if there is one.
+            pub fn from_value(name: &str, value: Value) -> Result<Self, serde_json::Error> {
+                match name {
+                    $($name => serde_json::from_value(value).map(Plugin::$variant),)*
+                    _ => Ok(Plugin::Other(name.to_owned(), value)),
+                }
+            }
+
+            pub fn to_value(&self) -> Value {
+                match self {
+                    $(Plugin::$variant(config) => {
+                        serde_json::to_value(config).expect("plugin config serializes to json")
+                    })*
+                    Plugin::Other(_, value) => value.clone(),
+                }
+            }
+        }
+
+        impl Serialize for Plugin {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                match self {
+                    $(Plugin::$variant(config) => config.serialize(serializer),)*
+                    Plugin::Other(_, value) => value.serialize(serializer),
+                }
+            }
+        }
+
+        $(
+            impl TypedPlugin for $variant {
+                const NAME: &'static str = $name;
+            }
+
+            impl From<$variant> for Plugin {
+                fn from(config: $variant) -> Self {
+                    Plugin::$variant(config)
+                }
+            }
+        )*
+    };
+}
+
+catalogue! {
+    BasicAuth => "basic-auth",
+    Cors => "cors",
+    HmacAuth => "hmac-auth",
+    IpRestriction => "ip-restriction",
+    JwtAuth => "jwt-auth",
+    KeyAuth => "key-auth",
+    LimitConn => "limit-conn",
+    LimitCount => "limit-count",
+    LimitReq => "limit-req",
+    MqttProxy => "mqtt-proxy",
+    OpenidConnect => "openid-connect",
+    Prometheus => "prometheus",
+    ProxyRewrite => "proxy-rewrite",
+    RequestId => "request-id",
+    ResponseRewrite => "response-rewrite",
+}
+
+/// The `plugins` object of a route, service, consumer or credential, keyed by plugin name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plugins(BTreeMap<String, Plugin>);
+
+impl Plugins {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn with(mut self, plugin: impl Into<Plugin>) -> Self {
+        self.insert(plugin);
+        self
+    }
+
+    pub fn insert(&mut self, plugin: impl Into<Plugin>) -> Option<Plugin> {
+        let plugin = plugin.into();
+        self.0.insert(plugin.name().to_owned(), plugin)
+    }
+
+    /// Inserts a [`TypedPlugin`] defined outside the catalogue, stored as [`Plugin::Other`]
+    /// unless `P::NAME` is a catalogue plugin, in which case it is parsed into that variant.
+    pub fn set<P>(&mut self, plugin: P) -> Result<Option<Plugin>, serde_json::Error>
+    where
+        P: TypedPlugin,
+    {
+        let plugin = Plugin::from_value(P::NAME, serde_json::to_value(plugin)?)?;
+        Ok(self.insert(plugin))
+    }
+
+    /// Names of the plugins that cannot run in `subsystem`.
+    ///
+    /// Only bundled plugins are known; any other plugin is assumed to be an HTTP plugin.
+    pub fn incompatible_with(&self, subsystem: Subsystem) -> Vec<&str> {
+        self.0
+            .keys()
+            .map(String::as_str)
+            .filter(|name| match subsystem {
+                Subsystem::Http => STREAM_ONLY_PLUGINS.contains(name),
+                Subsystem::Stream => !STREAM_PLUGINS.contains(name),
+            })
+            .collect()
+    }
+
+    /// Catalogue plugins kept as [`Plugin::Other`] because their configuration does not fit
+    /// the typed struct, e.g. a misspelled or unmodelled field, with the reason.
+    pub fn invalid(&self) -> Vec<(&str, serde_json::Error)> {
+        self.0
+            .iter()
+            .filter_map(|(name, plugin)| match plugin {
+                Plugin::Other(_, value) => Plugin::from_value(name, value.clone())
+                    .err()
+                    .map(|err| (name.as_str(), err)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the typed configuration of `P`, if the plugin is present.
+    pub fn get_typed<P>(&self) -> Option<Result<P, serde_json::Error>>
+    where
+        P: TypedPlugin,
+    {
+        self.0
+            .get(P::NAME)
+            .map(|plugin| serde_json::from_value(plugin.to_value()))
+    }
+}
+
+impl Deref for Plugins {
+    type Target = BTreeMap<String, Plugin>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Plugins {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl FromIterator<Plugin> for Plugins {
+    fn from_iter<I: IntoIterator<Item = Plugin>>(iter: I) -> Self {
+        Plugins(
+            iter.into_iter()
+                .map(|plugin| (plugin.name().to_owned(), plugin))
+                .collect(),
+        )
+    }
+}
+
+impl Serialize for Plugins {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Plugins {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        // A config that does not fit its typed struct must not fail the whole resource.
+        let plugins = BTreeMap::<String, Value>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(name, value)| {
+                let plugin = Plugin::from_value(&name, value.clone())
+                    .unwrap_or_else(|_| Plugin::Other(name.clone(), value));
+                (name, plugin)
+            })
+            .collect();
+        Ok(Plugins(plugins))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeyAuth {
+    /// Consumer side: the key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_credentials: Option<bool>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BasicAuth {
+    /// Consumer side: the username.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Consumer side: the password.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_credentials: Option<bool>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JwtAuth {
+    /// Consumer side: the key identifying the consumer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base64_secret: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lifetime_grace_period: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cookie: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_credentials: Option<bool>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HmacAuth {
+    /// Consumer side: the key id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+    /// Consumer side: the signing secret.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_algorithms: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock_skew: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signed_headers: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validate_request_body: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_credentials: Option<bool>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+/// Stream plugin that routes MQTT connections.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MqttProxy {
+    pub protocol_name: String,
+    pub protocol_level: u8,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpenidConnect {
+    pub client_id: String,
+    pub client_secret: String,
+    pub discovery: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_scopes: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub realm: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bearer_only: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unauth_action: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logout_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_logout_redirect_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssl_verify: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub introspection_endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub introspection_endpoint_auth_method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_endpoint_auth_method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_jwks: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_pkce: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_access_token_header: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_token_in_authorization_header: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_id_token_header: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_userinfo_header: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub set_refresh_token_header: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<Value>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LimitCount {
+    pub count: u64,
+    pub time_window: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejected_code: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejected_msg: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_degradation: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_limit_quota_header: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis_host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis_port: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis_password: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis_database: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis_cluster_nodes: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redis_cluster_name: Option<String>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LimitReq {
+    pub rate: f64,
+    pub burst: f64,
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejected_code: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejected_msg: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nodelay: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_degradation: Option<bool>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LimitConn {
+    pub conn: u64,
+    pub burst: u64,
+    pub default_conn_delay: f64,
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub only_use_default_delay: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejected_code: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejected_msg: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_degradation: Option<bool>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Cors {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_origins: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_methods: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_headers: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expose_headers: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_credential: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_origins_by_regex: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_origins_by_metadata: Option<Vec<String>>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyRewrite {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex_uri: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
+    /// Either `{"set": {..}, "add": {..}, "remove": [..]}` or a flat header map.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_real_request_uri_unsafe: Option<bool>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResponseRewrite {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_code: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_base64: Option<bool>,
+    /// Either `{"set": {..}, "add": [..], "remove": [..]}` or a flat header map.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vars: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filters: Option<Value>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IpRestriction {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub whitelist: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blacklist: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_code: Option<u16>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestId {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_in_response: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub algorithm: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range_id: Option<Value>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Prometheus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefer_name: Option<bool>,
+    #[serde(rename = "_meta", default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HttpLoggerMetadata {
+    /// Set by APISIX to the plugin name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Log entry fields, e.g. `{"host": "$host", "client_ip": "$remote_addr"}`.
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub log_format: Map<String, Value>,
+}
+
+impl PluginMetadata for HttpLoggerMetadata {
+    const NAME: &'static str = "http-logger";
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ErrorLogLoggerMetadata {
+    /// Set by APISIX to the plugin name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tcp: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skywalking: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clickhouse: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kafka: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keepalive: Option<u64>,
+}
+
+impl PluginMetadata for ErrorLogLoggerMetadata {
+    const NAME: &'static str = "error-log-logger";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_typed_plugins() {
+        let plugins = Plugins::new()
+            .with(KeyAuth {
+                key: Some("partner-key".into()),
+                ..Default::default()
+            })
+            .with(JwtAuth {
+                key: Some("partner".into()),
+                secret: Some("s3cr3t".into()),
+                algorithm: Some("HS256".into()),
+                ..Default::default()
+            });
+
+        assert_eq!(
+            serde_json::to_value(&plugins).unwrap(),
+            json!({
+                "key-auth": {"key": "partner-key"},
+                "jwt-auth": {"key": "partner", "secret": "s3cr3t", "algorithm": "HS256"}
+            })
+        );
+
+        let key_auth = plugins.get_typed::<KeyAuth>().unwrap().unwrap();
+        assert_eq!(key_auth.key.as_deref(), Some("partner-key"));
+        assert!(plugins.get_typed::<BasicAuth>().is_none());
+    }
+
+    #[test]
+    fn test_catalogue_and_fallback() {
+        let plugins: Plugins = serde_json::from_value(json!({
+            "limit-count": {"count": 100, "time_window": 60, "rejected_code": 429},
+            "hmac-auth": {"key_id": "k", "secret_key": "s", "max_req_body": 1024},
+            "ext-plugin-pre-req": {"conf": [{"name": "auth", "value": "{}"}]}
+        }))
+        .unwrap();
+
+        let Some(Plugin::LimitCount(limit_count)) = plugins.get("limit-count") else {
+            panic!("expected typed limit-count");
+        };
+        assert_eq!(limit_count.rejected_code, Some(429));
+
+        let Some(Plugin::Other(_, hmac)) = plugins.get("hmac-auth") else {
+            panic!("expected raw hmac-auth");
+        };
+        assert_eq!(hmac["max_req_body"], 1024);
+
+        let Some(Plugin::Other(name, value)) = plugins.get("ext-plugin-pre-req") else {
+            panic!("expected raw ext-plugin-pre-req");
+        };
+        assert_eq!(name, "ext-plugin-pre-req");
+        assert_eq!(value["conf"][0]["name"], "auth");
+    }
+
+    #[test]
+    fn test_invalid_known_plugin() {
+        let raw = json!({
+            "limit-count": {"count": "ten", "time_window": 60},
+            "limit-req": {"rate": 1, "burst": 0, "key": "remote_addr", "reject_code": 503},
+            "cors": {"_meta": {"disable": true}}
+        });
+        let plugins: Plugins = serde_json::from_value(raw.clone()).unwrap();
+
+        let invalid: Vec<_> = plugins
+            .invalid()
+            .into_iter()
+            .map(|(name, err)| format!("{name}: {err}"))
+            .collect();
+        assert_eq!(invalid.len(), 2);
+        assert!(invalid[0].starts_with("limit-count: invalid type"));
+        assert!(invalid[1].starts_with("limit-req: unknown field `reject_code`"));
+        assert!(matches!(plugins.get("cors"), Some(Plugin::Cors(_))));
+        assert_eq!(serde_json::to_value(&plugins).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_incompatible_with() {
+        let plugins = Plugins::new()
+            .with(MqttProxy {
+                protocol_name: "MQTT".into(),
+                protocol_level: 4,
+                ..Default::default()
+            })
+            .with(IpRestriction::default())
+            .with(Cors::default());
+
+        assert_eq!(plugins.incompatible_with(Subsystem::Http), ["mqtt-proxy"]);
+        assert_eq!(plugins.incompatible_with(Subsystem::Stream), ["cors"]);
+    }
+
+    #[test]
+    fn test_set_custom_plugin() {
+        #[derive(Serialize, Deserialize)]
+        struct MyPlugin {
+            enabled: bool,
+        }
+
+        impl TypedPlugin for MyPlugin {
+            const NAME: &'static str = "my-plugin";
+        }
+
+        let mut plugins = Plugins::new();
+        plugins.set(MyPlugin { enabled: true }).unwrap();
+        plugins
+            .set(Cors {
+                allow_origins: Some("**".into()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert!(matches!(plugins.get("cors"), Some(Plugin::Cors(_))));
+        assert!(plugins.get_typed::<MyPlugin>().unwrap().unwrap().enabled);
+    }
+}
@@ -0,0 +1,184 @@
+use std::{fs, io, path::Path};
+
+use thiserror::Error;
+use x509_parser::{
+    extensions::GeneralName,
+    pem::Pem,
+    prelude::{FromDer, X509Certificate},
+};
+
+use super::{Ssl, SslType};
+
+#[derive(Error, Debug)]
+pub enum SslError {
+    #[error("failed to read pem file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("no certificate found in pem")]
+    MissingCertificate,
+
+    #[error("invalid certificate: {0}")]
+    InvalidCertificate(String),
+
+    #[error("certificate has neither dns subject alternative names nor a common name")]
+    MissingSni,
+}
+
+/// Details of the leaf certificate of an SSL object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateInfo {
+    /// DNS subject alternative names, or the subject common name if there are none.
+    pub snis: Vec<String>,
+    /// Unix timestamp.
+    pub not_before: i64,
+    /// Unix timestamp.
+    pub not_after: i64,
+}
+
+impl CertificateInfo {
+    pub fn parse(cert_pem: &str) -> Result<Self, SslError> {
+        let pem = Pem::iter_from_buffer(cert_pem.as_bytes())
+            .next()
+            .ok_or(SslError::MissingCertificate)?
+            .map_err(|err| SslError::InvalidCertificate(err.to_string()))?;
+        let (_, cert) = X509Certificate::from_der(&pem.contents)
+            .map_err(|err| SslError::InvalidCertificate(err.to_string()))?;
+
+        let mut snis: Vec<String> = cert
+            .subject_alternative_name()
+            .map_err(|err| SslError::InvalidCertificate(err.to_string()))?
+            .map(|san| {
+                san.value
+                    .general_names
+                    .iter()
+                    .filter_map(|name| match name {
+                        GeneralName::DNSName(dns) => Some(dns.to_string()),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if snis.is_empty() {
+            snis.extend(
+                cert.subject()
+                    .iter_common_name()
+                    .filter_map(|cn| cn.as_str().ok())
+                    .map(str::to_owned),
+            );
+        }
+
+        Ok(Self {
+            snis,
+            not_before: cert.validity().not_before.timestamp(),
+            not_after: cert.validity().not_after.timestamp(),
+        })
+    }
+
+    pub fn is_valid_at(&self, timestamp: i64) -> bool {
+        self.not_before <= timestamp && timestamp <= self.not_after
+    }
+}
+
+impl Ssl {
+    /// Builds a server `Ssl` from a PEM certificate chain and private key, taking the SNIs
+    /// from the leaf certificate. Fails with [`SslError::MissingSni`] when it names no host.
+    pub fn from_pem(
+        cert_pem: impl Into<String>,
+        key_pem: impl Into<String>,
+    ) -> Result<Self, SslError> {
+        let cert = cert_pem.into();
+        let info = CertificateInfo::parse(&cert)?;
+        // APISIX requires at least one SNI.
+        if info.snis.is_empty() {
+            return Err(SslError::MissingSni);
+        }
+
+        Ok(Ssl {
+            ssl_type: Some(SslType::Server),
+            snis: Some(info.snis),
+            cert: Some(cert),
+            key: Some(key_pem.into()),
+            ..Default::default()
+        })
+    }
+
+    pub fn from_pem_files(
+        cert_path: impl AsRef<Path>,
+        key_path: impl AsRef<Path>,
+    ) -> Result<Self, SslError> {
+        Self::from_pem(
+            fs::read_to_string(cert_path)?,
+            fs::read_to_string(key_path)?,
+        )
+    }
+
+    /// Parses the leaf certificate in `cert`.
+    pub fn certificate_info(&self) -> Result<CertificateInfo, SslError> {
+        CertificateInfo::parse(self.cert.as_deref().ok_or(SslError::MissingCertificate)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn test_from_pem_files() {
+        let certified = rcgen::generate_simple_self_signed(vec![
+            "api.example.com".into(),
+            "*.example.com".into(),
+        ])
+        .unwrap();
+        let dir = std::env::temp_dir().join(format!("apisix-rs-ssl-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        fs::write(&cert_path, certified.cert.pem()).unwrap();
+        fs::write(&key_path, certified.key_pair.serialize_pem()).unwrap();
+
+        let ssl = Ssl::from_pem_files(&cert_path, &key_path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(ssl.ssl_type, Some(SslType::Server));
+        assert_eq!(
+            ssl.snis.as_deref().unwrap(),
+            ["api.example.com", "*.example.com"]
+        );
+        assert!(ssl.key.as_deref().unwrap().contains("PRIVATE KEY"));
+
+        let info = ssl.certificate_info().unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        assert!(info.is_valid_at(now));
+        assert!(!info.is_valid_at(info.not_after + 1));
+    }
+
+    #[test]
+    fn test_missing_certificate() {
+        assert!(matches!(
+            Ssl::from_pem("not a pem", "").unwrap_err(),
+            SslError::MissingCertificate
+        ));
+        assert!(matches!(
+            Ssl::default().certificate_info().unwrap_err(),
+            SslError::MissingCertificate
+        ));
+    }
+
+    #[test]
+    fn test_missing_sni() {
+        let key_pair = rcgen::KeyPair::generate().unwrap();
+        let mut params = rcgen::CertificateParams::new(Vec::<String>::new()).unwrap();
+        params.distinguished_name = rcgen::DistinguishedName::new();
+        let cert = params.self_signed(&key_pair).unwrap();
+
+        assert!(CertificateInfo::parse(&cert.pem()).unwrap().snis.is_empty());
+        assert!(matches!(
+            Ssl::from_pem(cert.pem(), key_pair.serialize_pem()).unwrap_err(),
+            SslError::MissingSni
+        ));
+    }
+}
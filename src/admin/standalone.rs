@@ -0,0 +1,184 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use super::{
+    Consumer, ConsumerGroup, GlobalRule, PluginConfig, Route, Service, Ssl, StreamRoute, Upstream,
+};
+
+/// Line that APISIX requires at the end of `apisix.yaml` before it loads the file.
+pub const END_MARKER: &str = "#END";
+
+#[derive(Error, Debug)]
+pub enum StandaloneError {
+    #[error("failed to read config file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid yaml: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("config is not terminated by {END_MARKER}")]
+    MissingEnd,
+}
+
+/// The `apisix.yaml` (or `apisix.json`) document read by APISIX in standalone mode.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StandaloneConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub routes: Vec<Route>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub services: Vec<Service>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub upstreams: Vec<Upstream>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub consumers: Vec<Consumer>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub consumer_groups: Vec<ConsumerGroup>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ssls: Vec<Ssl>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub global_rules: Vec<GlobalRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugin_configs: Vec<PluginConfig>,
+    /// Entries are `{"id": "<plugin name>", ...metadata}`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugin_metadata: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stream_routes: Vec<StreamRoute>,
+}
+
+impl StandaloneConfig {
+    /// Parses an `apisix.yaml` document, which must end with `#END`.
+    pub fn from_yaml(input: &str) -> Result<Self, StandaloneError> {
+        let terminated = input
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .is_some_and(|line| line.trim() == END_MARKER);
+        if !terminated {
+            return Err(StandaloneError::MissingEnd);
+        }
+
+        // An empty document (only `#END`) deserializes to unit, not a mapping.
+        match serde_yaml::from_str::<Option<Self>>(input)? {
+            Some(config) => Ok(config),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_yaml_file(path: impl AsRef<Path>) -> Result<Self, StandaloneError> {
+        Self::from_yaml(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_json(input: &str) -> Result<Self, StandaloneError> {
+        Ok(serde_json::from_str(input)?)
+    }
+
+    /// Serializes to YAML, terminated by `#END`.
+    pub fn to_yaml(&self) -> Result<String, StandaloneError> {
+        let mut output = serde_yaml::to_string(self)?;
+        output.push_str(END_MARKER);
+        output.push('\n');
+        Ok(output)
+    }
+
+    pub fn to_json(&self) -> Result<String, StandaloneError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn write_yaml_file(&self, path: impl AsRef<Path>) -> Result<(), StandaloneError> {
+        Ok(fs::write(path, self.to_yaml()?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::{KeyAuth, LimitCount, Node, Nodes, Plugin, Plugins};
+
+    const CONFIG: &str = r#"
+routes:
+  - id: httpbin
+    uri: /anything/*
+    upstream_id: httpbin
+    plugins:
+      limit-count:
+        count: 10
+        time_window: 60
+upstreams:
+  - id: httpbin
+    type: roundrobin
+    nodes:
+      "httpbin.org:80": 1
+consumers:
+  - username: partner
+    plugins:
+      key-auth:
+        key: partner-key
+#END
+"#;
+
+    #[test]
+    fn test_parse() {
+        let config = StandaloneConfig::from_yaml(CONFIG).unwrap();
+
+        assert_eq!(config.routes[0].id.as_deref(), Some("httpbin"));
+        assert!(matches!(
+            config.routes[0].plugins.get("limit-count"),
+            Some(Plugin::LimitCount(LimitCount { count: 10, .. }))
+        ));
+        assert_eq!(config.upstreams.len(), 1);
+        assert_eq!(config.consumers[0].username, "partner");
+    }
+
+    #[test]
+    fn test_missing_end() {
+        let input = CONFIG.replace("#END", "");
+        assert!(matches!(
+            StandaloneConfig::from_yaml(&input),
+            Err(StandaloneError::MissingEnd)
+        ));
+        assert!(StandaloneConfig::from_yaml("#END\n")
+            .unwrap()
+            .routes
+            .is_empty());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let config = StandaloneConfig {
+            upstreams: vec![Upstream {
+                id: Some("backend".into()),
+                nodes: Some(Nodes::List(vec![Node {
+                    host: "10.0.0.1".into(),
+                    port: 8080,
+                    weight: 1,
+                    ..Default::default()
+                }])),
+                ..Default::default()
+            }],
+            consumers: vec![Consumer {
+                username: "partner".into(),
+                plugins: Plugins::new().with(KeyAuth {
+                    key: Some("partner-key".into()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let yaml = config.to_yaml().unwrap();
+        assert!(yaml.ends_with("#END\n"));
+        assert!(!yaml.contains("routes"));
+        assert_eq!(StandaloneConfig::from_yaml(&yaml).unwrap(), config);
+
+        let json = config.to_json().unwrap();
+        assert_eq!(StandaloneConfig::from_json(&json).unwrap(), config);
+    }
+}
@@ -0,0 +1,937 @@
+//! Declarative sync: diff a desired set of resources against the live Admin API state.
+//!
+//! Objects are compared in full after normalization, so a field dropped from the desired
+//! object is drift just like a changed one. Normalization removes the fields APISIX manages
+//! itself (see [`server_fields`]), sorts set-like arrays such as `methods` and `hosts`, and
+//! brings upstream `nodes` into one shape. A live field holding the value APISIX fills in by
+//! default (see [`server_default`]) is not drift when the desired object omits it. Changed
+//! objects are replaced as a whole with a PUT of the desired object as it was added.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+};
+
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use super::{Credential, Resource, ResourceKind};
+
+/// Order in which creates and updates are applied; deletes run in reverse.
+const SYNC_ORDER: [ResourceKind; 10] = [
+    ResourceKind::Ssl,
+    ResourceKind::Upstream,
+    ResourceKind::Service,
+    ResourceKind::PluginConfig,
+    ResourceKind::GlobalRule,
+    ResourceKind::ConsumerGroup,
+    ResourceKind::Consumer,
+    ResourceKind::Credential,
+    ResourceKind::Route,
+    ResourceKind::StreamRoute,
+];
+
+/// Top-level arrays whose order APISIX does not preserve or care about.
+const SET_FIELDS: [&str; 5] = ["hosts", "methods", "remote_addrs", "snis", "uris"];
+
+#[derive(Error, Debug)]
+pub enum SyncError {
+    #[error("{0:?} has no id")]
+    MissingId(ResourceKind),
+
+    #[error("failed to serialize resource: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Identifies a resource; credential ids are `{username}/{credential id}`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ResourceKey {
+    pub kind: ResourceKind,
+    pub id: String,
+}
+
+/// Where live resources are read from and changes are written to.
+pub trait Backend {
+    type Error: std::error::Error;
+
+    fn list(
+        &self,
+        kind: ResourceKind,
+    ) -> impl Future<Output = Result<Vec<(String, Value)>, Self::Error>>;
+
+    fn put(
+        &self,
+        kind: ResourceKind,
+        id: &str,
+        value: &Value,
+    ) -> impl Future<Output = Result<(), Self::Error>>;
+
+    fn delete(&self, kind: ResourceKind, id: &str)
+        -> impl Future<Output = Result<(), Self::Error>>;
+}
+
+/// The resources that should exist after a sync.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DesiredState {
+    resources: BTreeMap<ResourceKey, Value>,
+    kinds: BTreeSet<ResourceKind>,
+}
+
+impl DesiredState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add<R>(&mut self, resource: &R) -> Result<&mut Self, SyncError>
+    where
+        R: Resource,
+    {
+        let id = resource
+            .id()
+            .ok_or(SyncError::MissingId(R::KIND))?
+            .to_owned();
+        self.insert(R::KIND, id, serde_json::to_value(resource)?);
+        Ok(self)
+    }
+
+    pub fn add_all<R>(&mut self, resources: &[R]) -> Result<&mut Self, SyncError>
+    where
+        R: Resource,
+    {
+        for resource in resources {
+            self.add(resource)?;
+        }
+        Ok(self)
+    }
+
+    pub fn add_credential(
+        &mut self,
+        username: &str,
+        credential: &Credential,
+    ) -> Result<&mut Self, SyncError> {
+        let id = credential
+            .id
+            .as_deref()
+            .ok_or(SyncError::MissingId(ResourceKind::Credential))?;
+        let value = serde_json::to_value(credential)?;
+        self.insert(ResourceKind::Credential, format!("{username}/{id}"), value);
+        Ok(self)
+    }
+
+    /// Compares `kind` even when no resource of that kind is desired, so pruning removes them all.
+    pub fn manage(&mut self, kind: ResourceKind) -> &mut Self {
+        self.kinds.insert(kind);
+        self
+    }
+
+    /// Collects every resource of a standalone config; each must carry an id.
+    #[cfg(feature = "standalone")]
+    pub fn from_standalone(config: &super::StandaloneConfig) -> Result<Self, SyncError> {
+        let mut desired = DesiredState::new();
+        desired
+            .add_all(&config.routes)?
+            .add_all(&config.services)?
+            .add_all(&config.upstreams)?
+            .add_all(&config.consumers)?
+            .add_all(&config.consumer_groups)?
+            .add_all(&config.ssls)?
+            .add_all(&config.global_rules)?
+            .add_all(&config.plugin_configs)?
+            .add_all(&config.stream_routes)?;
+        Ok(desired)
+    }
+
+    fn insert(&mut self, kind: ResourceKind, id: String, value: Value) {
+        self.kinds.insert(kind);
+        self.resources.insert(ResourceKey { kind, id }, value);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncOptions {
+    /// Delete live resources of the managed kinds that are not desired.
+    pub prune: bool,
+    /// Only compute the plan.
+    pub dry_run: bool,
+    /// Update SSLs whose desired object carries a private key even if nothing else changed.
+    /// APISIX never returns keys, so a rotated key is otherwise not noticed.
+    pub force_ssl_keys: bool,
+}
+
+/// A changed field, located by a JSON pointer into the resource.
+///
+/// SSL private keys are reported without values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    pub pointer: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A write to the live state; `value` is the desired object as added, not normalized.
+pub enum Change {
+    Create {
+        key: ResourceKey,
+        value: Value,
+    },
+    Update {
+        key: ResourceKey,
+        value: Value,
+        diffs: Vec<FieldDiff>,
+    },
+    Delete {
+        key: ResourceKey,
+    },
+}
+
+impl Change {
+    pub fn key(&self) -> &ResourceKey {
+        match self {
+            Change::Create { key, .. } | Change::Update { key, .. } | Change::Delete { key } => key,
+        }
+    }
+}
+
+/// Changes in the order they are applied.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Computes the changes that turn the live state of `backend` into `desired`.
+pub async fn plan<B>(
+    backend: &B,
+    desired: &DesiredState,
+    options: SyncOptions,
+) -> Result<Plan, B::Error>
+where
+    B: Backend,
+{
+    let mut upserts = Vec::new();
+    let mut deletes = Vec::new();
+
+    for kind in SYNC_ORDER {
+        if !desired.kinds.contains(&kind) {
+            continue;
+        }
+
+        let live: BTreeMap<String, Value> = backend
+            .list(kind)
+            .await?
+            .into_iter()
+            .map(|(id, value)| (id, normalize(kind, value)))
+            .collect();
+
+        let resources = desired
+            .resources
+            .range(kind_start(kind)..)
+            .take_while(|(key, _)| key.kind == kind);
+        for (key, value) in resources {
+            match live.get(&key.id) {
+                None => upserts.push(Change::Create {
+                    key: key.clone(),
+                    value: value.clone(),
+                }),
+                Some(current) => {
+                    let mut diffs = Vec::new();
+                    diff_value(
+                        kind,
+                        "",
+                        current,
+                        &normalize(kind, value.clone()),
+                        &mut diffs,
+                    );
+                    if options.force_ssl_keys && kind == ResourceKind::Ssl {
+                        diffs.extend(ssl_key_diffs(value));
+                    }
+                    if !diffs.is_empty() {
+                        upserts.push(Change::Update {
+                            key: key.clone(),
+                            value: value.clone(),
+                            diffs,
+                        });
+                    }
+                }
+            }
+        }
+
+        if options.prune {
+            for id in live.keys() {
+                let key = ResourceKey {
+                    kind,
+                    id: id.clone(),
+                };
+                if !desired.resources.contains_key(&key) {
+                    deletes.push(Change::Delete { key });
+                }
+            }
+        }
+    }
+
+    deletes.reverse();
+    upserts.extend(deletes);
+    Ok(Plan { changes: upserts })
+}
+
+/// Applies `plan` in order, stopping at the first failure.
+pub async fn apply<B>(backend: &B, plan: &Plan) -> Result<(), B::Error>
+where
+    B: Backend,
+{
+    for change in &plan.changes {
+        match change {
+            Change::Create { key, value } | Change::Update { key, value, .. } => {
+                backend.put(key.kind, &key.id, value).await?
+            }
+            Change::Delete { key } => backend.delete(key.kind, &key.id).await?,
+        }
+    }
+    Ok(())
+}
+
+/// Plans and, unless `options.dry_run` is set, applies the changes.
+pub async fn sync<B>(
+    backend: &B,
+    desired: &DesiredState,
+    options: SyncOptions,
+) -> Result<Plan, B::Error>
+where
+    B: Backend,
+{
+    let plan = plan(backend, desired, options).await?;
+    if !options.dry_run {
+        apply(backend, &plan).await?;
+    }
+    Ok(plan)
+}
+
+fn kind_start(kind: ResourceKind) -> ResourceKey {
+    ResourceKey {
+        kind,
+        id: String::new(),
+    }
+}
+
+/// Fields maintained by APISIX that are removed from both sides before comparing.
+///
+/// GET never returns an SSL private key in plain text, so `key` and `keys` cannot be compared;
+/// set [`SyncOptions::force_ssl_keys`] to update SSLs with a key anyway.
+pub fn server_fields(kind: ResourceKind) -> &'static [&'static str] {
+    match kind {
+        ResourceKind::Ssl => &[
+            "id",
+            "create_time",
+            "update_time",
+            "key",
+            "keys",
+            "validity_start",
+            "validity_end",
+        ],
+        _ => &["id", "create_time", "update_time"],
+    }
+}
+
+/// The value APISIX stores for the field at `pointer` when the object omits it.
+pub fn server_default(kind: ResourceKind, pointer: &str) -> Option<Value> {
+    let upstream_field = match (kind, pointer) {
+        (ResourceKind::Upstream, _) => pointer.strip_prefix('/'),
+        (ResourceKind::Route | ResourceKind::Service | ResourceKind::StreamRoute, _) => {
+            pointer.strip_prefix("/upstream/")
+        }
+        _ => None,
+    };
+    match upstream_field {
+        Some("scheme") => return Some(json!("http")),
+        Some("pass_host") => return Some(json!("pass")),
+        Some("hash_on") => return Some(json!("vars")),
+        _ => {}
+    }
+
+    match (kind, pointer) {
+        (_, "/plugins") => Some(json!({})),
+        (ResourceKind::Route | ResourceKind::Ssl, "/status") => Some(json!(1)),
+        (ResourceKind::Route, "/priority") => Some(json!(0)),
+        (ResourceKind::Ssl, "/type") => Some(json!("server")),
+        _ => None,
+    }
+}
+
+fn normalize(kind: ResourceKind, mut value: Value) -> Value {
+    let Some(object) = value.as_object_mut() else {
+        return value;
+    };
+    for field in server_fields(kind) {
+        object.remove(*field);
+    }
+    for field in SET_FIELDS {
+        if let Some(Value::Array(items)) = object.get_mut(field) {
+            items.sort_by_key(Value::to_string);
+        }
+    }
+    match kind {
+        ResourceKind::Upstream => normalize_nodes(object),
+        ResourceKind::Route | ResourceKind::Service | ResourceKind::StreamRoute => {
+            if let Some(Value::Object(upstream)) = object.get_mut("upstream") {
+                normalize_nodes(upstream);
+            }
+        }
+        _ => {}
+    }
+    value
+}
+
+/// Brings `nodes` into the list form sorted by address; the `"host:port": weight` map form is
+/// shorthand for it.
+fn normalize_nodes(upstream: &mut Map<String, Value>) {
+    let mut nodes = match upstream.get("nodes") {
+        Some(Value::Object(nodes)) => nodes
+            .iter()
+            .map(|(address, weight)| {
+                let mut node = Map::new();
+                match address.rsplit_once(':') {
+                    Some((host, port)) if port.parse::<u16>().is_ok() => {
+                        node.insert("host".into(), json!(host));
+                        node.insert("port".into(), json!(port.parse::<u16>().unwrap()));
+                    }
+                    _ => {
+                        node.insert("host".into(), json!(address));
+                    }
+                }
+                node.insert("weight".into(), weight.clone());
+                Value::Object(node)
+            })
+            .collect(),
+        Some(Value::Array(nodes)) => nodes.clone(),
+        _ => return,
+    };
+    for node in &mut nodes {
+        if let Some(node) = node.as_object_mut() {
+            if node.get("priority") == Some(&json!(0)) {
+                node.remove("priority");
+            }
+        }
+    }
+    nodes.sort_by_key(|node| format!("{}:{}", node["host"], node["port"]));
+    upstream.insert("nodes".into(), Value::Array(nodes));
+}
+
+fn diff_value(
+    kind: ResourceKind,
+    pointer: &str,
+    live: &Value,
+    desired: &Value,
+    diffs: &mut Vec<FieldDiff>,
+) {
+    match (live, desired) {
+        (Value::Object(live), Value::Object(desired)) => {
+            diff_object(kind, pointer, live, desired, diffs)
+        }
+        (Value::Number(a), Value::Number(b)) if a.as_f64() == b.as_f64() => {}
+        _ if live != desired => diffs.push(FieldDiff {
+            pointer: pointer.to_owned(),
+            old: Some(live.clone()),
+            new: Some(desired.clone()),
+        }),
+        _ => {}
+    }
+}
+
+fn diff_object(
+    kind: ResourceKind,
+    pointer: &str,
+    live: &Map<String, Value>,
+    desired: &Map<String, Value>,
+    diffs: &mut Vec<FieldDiff>,
+) {
+    for (field, value) in desired {
+        let field_pointer = format!("{pointer}/{}", escape(field));
+        match live.get(field) {
+            Some(current) => diff_value(kind, &field_pointer, current, value, diffs),
+            None => diffs.push(FieldDiff {
+                pointer: field_pointer,
+                old: None,
+                new: Some(value.clone()),
+            }),
+        }
+    }
+
+    // Fields omitted from the desired object are removed by the PUT.
+    for (field, value) in live {
+        if desired.contains_key(field) {
+            continue;
+        }
+        let field_pointer = format!("{pointer}/{}", escape(field));
+        if server_default(kind, &field_pointer).as_ref() == Some(value) {
+            continue;
+        }
+        diffs.push(FieldDiff {
+            pointer: field_pointer,
+            old: Some(value.clone()),
+            new: None,
+        });
+    }
+}
+
+fn ssl_key_diffs(desired: &Value) -> impl Iterator<Item = FieldDiff> + '_ {
+    ["key", "keys"]
+        .into_iter()
+        .filter(|field| desired.get(field).is_some())
+        .map(|field| FieldDiff {
+            pointer: format!("/{field}"),
+            old: None,
+            new: None,
+        })
+}
+
+fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(feature = "admin-client")]
+mod admin_client {
+    use reqwest::Method;
+    use serde_json::Value;
+
+    use super::Backend;
+    use crate::admin::{AdminClient, AdminError, ResourceKind};
+
+    impl Backend for AdminClient {
+        type Error = AdminError;
+
+        async fn list(&self, kind: ResourceKind) -> Result<Vec<(String, Value)>, AdminError> {
+            if kind != ResourceKind::Credential {
+                let items = self.api::<Value>(kind.collection()).list_all().await?;
+                return Ok(items
+                    .into_iter()
+                    .map(|item| (item.id().to_owned(), item.value))
+                    .collect());
+            }
+
+            let mut credentials = Vec::new();
+            for consumer in self.api::<Value>("consumers").list_all().await? {
+                let username = consumer.id();
+                for item in self
+                    .api::<Value>(format!("consumers/{username}/credentials"))
+                    .list_all()
+                    .await?
+                {
+                    credentials.push((format!("{username}/{}", item.id()), item.value));
+                }
+            }
+            Ok(credentials)
+        }
+
+        async fn put(&self, kind: ResourceKind, id: &str, value: &Value) -> Result<(), AdminError> {
+            match kind {
+                // Consumers are keyed by the username in the body.
+                ResourceKind::Consumer => {
+                    let request = self.request(Method::PUT, "consumers").json(value);
+                    self.send::<Value>(request).await?;
+                }
+                ResourceKind::Credential => {
+                    let (username, id) = split_credential(id);
+                    self.api::<Value>(format!("consumers/{username}/credentials"))
+                        .put(id, value)
+                        .await?;
+                }
+                _ => {
+                    self.api::<Value>(kind.collection()).put(id, value).await?;
+                }
+            }
+            Ok(())
+        }
+
+        async fn delete(&self, kind: ResourceKind, id: &str) -> Result<(), AdminError> {
+            match kind {
+                ResourceKind::Credential => {
+                    let (username, id) = split_credential(id);
+                    self.api::<Value>(format!("consumers/{username}/credentials"))
+                        .delete(id)
+                        .await
+                }
+                _ => self.api::<Value>(kind.collection()).delete(id).await,
+            }
+        }
+    }
+
+    fn split_credential(id: &str) -> (&str, &str) {
+        id.split_once('/').unwrap_or(("", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, convert::Infallible};
+
+    use super::*;
+    use crate::admin::{Consumer, KeyAuth, Plugins, Route, Upstream};
+    use serde_json::json;
+
+    #[derive(Default)]
+    struct InMemory {
+        state: RefCell<BTreeMap<ResourceKey, Value>>,
+        calls: RefCell<Vec<String>>,
+    }
+
+    impl InMemory {
+        fn with(self, kind: ResourceKind, id: &str, value: Value) -> Self {
+            let key = ResourceKey {
+                kind,
+                id: id.to_owned(),
+            };
+            self.state.borrow_mut().insert(key, value);
+            self
+        }
+    }
+
+    impl Backend for InMemory {
+        type Error = Infallible;
+
+        async fn list(&self, kind: ResourceKind) -> Result<Vec<(String, Value)>, Infallible> {
+            Ok(self
+                .state
+                .borrow()
+                .iter()
+                .filter(|(key, _)| key.kind == kind)
+                .map(|(key, value)| (key.id.clone(), value.clone()))
+                .collect())
+        }
+
+        async fn put(&self, kind: ResourceKind, id: &str, value: &Value) -> Result<(), Infallible> {
+            self.calls.borrow_mut().push(format!("put {kind:?} {id}"));
+            let key = ResourceKey {
+                kind,
+                id: id.to_owned(),
+            };
+            self.state.borrow_mut().insert(key, value.clone());
+            Ok(())
+        }
+
+        async fn delete(&self, kind: ResourceKind, id: &str) -> Result<(), Infallible> {
+            self.calls
+                .borrow_mut()
+                .push(format!("delete {kind:?} {id}"));
+            let key = ResourceKey {
+                kind,
+                id: id.to_owned(),
+            };
+            self.state.borrow_mut().remove(&key);
+            Ok(())
+        }
+    }
+
+    fn desired() -> DesiredState {
+        let mut desired = DesiredState::new();
+        desired
+            .add(&Route {
+                id: Some("orders".into()),
+                uri: Some("/orders/*".into()),
+                upstream_id: Some("backend".into()),
+                ..Default::default()
+            })
+            .unwrap()
+            .add(&Upstream {
+                id: Some("backend".into()),
+                ..Default::default()
+            })
+            .unwrap()
+            .add(&Consumer {
+                username: "partner".into(),
+                ..Default::default()
+            })
+            .unwrap()
+            .add_credential(
+                "partner",
+                &Credential {
+                    id: Some("key".into()),
+                    plugins: Plugins::new().with(KeyAuth {
+                        key: Some("partner-key".into()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        desired
+    }
+
+    #[tokio::test]
+    async fn test_apply_in_dependency_order() {
+        let backend = InMemory::default();
+
+        let plan = sync(&backend, &desired(), SyncOptions::default())
+            .await
+            .unwrap();
+
+        assert!(plan
+            .changes
+            .iter()
+            .all(|change| matches!(change, Change::Create { .. })));
+        assert_eq!(
+            *backend.calls.borrow(),
+            [
+                "put Upstream backend",
+                "put Consumer partner",
+                "put Credential partner/key",
+                "put Route orders",
+            ]
+        );
+
+        let plan = sync(&backend, &desired(), SyncOptions::default())
+            .await
+            .unwrap();
+        assert!(plan.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_field_diffs_ignore_server_defaults() {
+        let backend = InMemory::default()
+            .with(
+                ResourceKind::Route,
+                "orders",
+                json!({
+                    "id": "orders",
+                    "uri": "/orders",
+                    "upstream_id": "backend",
+                    "priority": 0,
+                    "status": 1,
+                    "plugins": {"prometheus": {}},
+                    "create_time": 1700000000,
+                }),
+            )
+            .with(
+                ResourceKind::Upstream,
+                "backend",
+                json!({"id": "backend", "pass_host": "pass", "timeout": {"connect": 6}}),
+            );
+        let mut desired = desired();
+        desired
+            .add(&Upstream {
+                id: Some("backend".into()),
+                timeout: Some(crate::admin::Timeout {
+                    connect: 6.0,
+                    send: 6.0,
+                    read: 6.0,
+                }),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let options = SyncOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        let plan = sync(&backend, &desired, options).await.unwrap();
+
+        assert!(backend.calls.borrow().is_empty());
+        let updates: BTreeMap<_, _> = plan
+            .changes
+            .iter()
+            .filter_map(|change| match change {
+                Change::Update { key, diffs, .. } => Some((key.id.as_str(), diffs.clone())),
+                _ => None,
+            })
+            .collect();
+
+        let pointers = |id| {
+            updates[id]
+                .iter()
+                .map(|diff| diff.pointer.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(pointers("backend"), ["/timeout/read", "/timeout/send"]);
+        assert_eq!(pointers("orders"), ["/uri", "/plugins"]);
+        assert_eq!(updates["orders"][0].new, Some(json!("/orders/*")));
+        assert_eq!(updates["orders"][1].new, None);
+    }
+
+    #[tokio::test]
+    async fn test_removed_field_is_drift() {
+        let backend = InMemory::default().with(
+            ResourceKind::Route,
+            "orders",
+            json!({
+                "uri": "/orders/*",
+                "upstream_id": "backend",
+                "hosts": ["api.example.com"],
+                "upstream": {"nodes": {"10.0.0.1:80": 1}, "timeout": {"connect": 3}},
+            }),
+        );
+        let mut desired = DesiredState::new();
+        desired
+            .add(&Route {
+                id: Some("orders".into()),
+                uri: Some("/orders/*".into()),
+                upstream: Some(
+                    serde_json::from_value(json!({"nodes": {"10.0.0.1:80": 1}})).unwrap(),
+                ),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let plan = sync(&backend, &desired, SyncOptions::default())
+            .await
+            .unwrap();
+
+        let Change::Update { diffs, .. } = &plan.changes[0] else {
+            panic!("expected an update, got {plan:?}");
+        };
+        let pointers: Vec<_> = diffs.iter().map(|diff| diff.pointer.as_str()).collect();
+        assert_eq!(pointers, ["/upstream/timeout", "/hosts", "/upstream_id"]);
+        assert!(diffs.iter().all(|diff| diff.new.is_none()));
+        assert_eq!(*backend.calls.borrow(), ["put Route orders"]);
+    }
+
+    #[tokio::test]
+    async fn test_normalized_resources_are_unchanged() {
+        let backend = InMemory::default()
+            .with(
+                ResourceKind::Ssl,
+                "api",
+                json!({
+                    "id": "api",
+                    "cert": "CERT",
+                    "snis": ["b.example.com", "a.example.com"],
+                    "status": 1,
+                    "type": "server",
+                    "validity_start": 1700000000,
+                    "validity_end": 1800000000,
+                }),
+            )
+            .with(
+                ResourceKind::Upstream,
+                "backend",
+                json!({
+                    "type": "roundrobin",
+                    "scheme": "http",
+                    "nodes": [
+                        {"host": "10.0.0.2", "port": 80, "weight": 1, "priority": 0},
+                        {"host": "10.0.0.1", "port": 80, "weight": 1},
+                    ],
+                }),
+            );
+        let mut desired = DesiredState::new();
+        desired
+            .add(&crate::admin::Ssl {
+                id: Some("api".into()),
+                cert: Some("CERT".into()),
+                key: Some("KEY".into()),
+                snis: Some(vec!["a.example.com".into(), "b.example.com".into()]),
+                ..Default::default()
+            })
+            .unwrap()
+            .add(
+                &serde_json::from_value::<Upstream>(json!({
+                    "id": "backend",
+                    "type": "roundrobin",
+                    "nodes": {"10.0.0.1:80": 1, "10.0.0.2:80": 1},
+                }))
+                .unwrap(),
+            )
+            .unwrap();
+
+        let plan = sync(&backend, &desired, SyncOptions::default())
+            .await
+            .unwrap();
+        assert!(plan.is_empty(), "{plan:?}");
+    }
+
+    #[tokio::test]
+    async fn test_ssl_keys() {
+        let ssl = crate::admin::Ssl {
+            id: Some("api".into()),
+            cert: Some("CERT".into()),
+            key: Some("KEY".into()),
+            snis: Some(vec!["api.example.com".into()]),
+            ..Default::default()
+        };
+        let mut desired = DesiredState::new();
+        desired.add(&ssl).unwrap();
+        let backend = InMemory::default();
+
+        sync(&backend, &desired, SyncOptions::default())
+            .await
+            .unwrap();
+        let stored = backend.state.borrow()[&ResourceKey {
+            kind: ResourceKind::Ssl,
+            id: "api".into(),
+        }]
+            .clone();
+        assert_eq!(stored, serde_json::to_value(&ssl).unwrap());
+
+        // The live SSL comes back without its key.
+        let backend = InMemory::default().with(
+            ResourceKind::Ssl,
+            "api",
+            json!({"id": "api", "cert": "CERT", "snis": ["api.example.com"]}),
+        );
+        let plan = sync(&backend, &desired, SyncOptions::default())
+            .await
+            .unwrap();
+        assert!(plan.is_empty());
+
+        let options = SyncOptions {
+            force_ssl_keys: true,
+            ..Default::default()
+        };
+        let plan = sync(&backend, &desired, options).await.unwrap();
+        let Change::Update { value, diffs, .. } = &plan.changes[0] else {
+            panic!("expected an update, got {plan:?}");
+        };
+        assert_eq!(value["key"], "KEY");
+        assert_eq!(
+            *diffs,
+            [FieldDiff {
+                pointer: "/key".into(),
+                old: None,
+                new: None,
+            }]
+        );
+        assert_eq!(*backend.calls.borrow(), ["put Ssl api"]);
+    }
+
+    #[tokio::test]
+    async fn test_prune() {
+        let backend = InMemory::default()
+            .with(ResourceKind::Route, "stale", json!({"uri": "/stale"}))
+            .with(ResourceKind::Upstream, "stale", json!({}))
+            .with(ResourceKind::Ssl, "unmanaged", json!({}));
+
+        let plan = sync(&backend, &desired(), SyncOptions::default())
+            .await
+            .unwrap();
+        assert!(!plan
+            .changes
+            .iter()
+            .any(|change| matches!(change, Change::Delete { .. })));
+
+        let options = SyncOptions {
+            prune: true,
+            ..Default::default()
+        };
+        sync(&backend, &desired(), options).await.unwrap();
+
+        let calls = backend.calls.borrow();
+        assert_eq!(
+            calls[calls.len() - 2..],
+            ["delete Route stale", "delete Upstream stale"]
+        );
+        assert!(backend.state.borrow().contains_key(&ResourceKey {
+            kind: ResourceKind::Ssl,
+            id: "unmanaged".into(),
+        }));
+    }
+
+    #[test]
+    fn test_missing_id() {
+        let err = DesiredState::new().add(&Route::default()).unwrap_err();
+        assert!(matches!(err, SyncError::MissingId(ResourceKind::Route)));
+    }
+}
@@ -0,0 +1,398 @@
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use jsonschema::Validator;
+use serde_json::Value;
+use thiserror::Error;
+
+use super::{Resource, ResourceKind, Subsystem};
+
+#[derive(Error, Debug)]
+pub enum SchemaError {
+    #[error("failed to read schema file: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid schema json: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid schema for {name}: {message}")]
+    InvalidSchema { name: String, message: String },
+}
+
+/// A single schema violation, located by a JSON pointer into the validated object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{pointer}: {}", self.message)
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub struct ValidationErrors(pub Vec<Violation>);
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, violation) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{violation}")?;
+        }
+        Ok(())
+    }
+}
+
+struct PluginSchemas {
+    schema: Validator,
+    consumer_schema: Option<Validator>,
+}
+
+/// Validates Admin API objects against the schemas exported by the Control API at `/v1/schema`.
+pub struct SchemaValidator {
+    main: HashMap<String, Validator>,
+    plugins: HashMap<String, PluginSchemas>,
+    stream_plugins: HashMap<String, PluginSchemas>,
+}
+
+impl SchemaValidator {
+    pub fn from_value(schema: &Value) -> Result<Self, SchemaError> {
+        let mut main = HashMap::new();
+        if let Some(schemas) = schema.get("main").and_then(Value::as_object) {
+            for (name, schema) in schemas {
+                main.insert(name.clone(), compile(name, schema)?);
+            }
+        }
+
+        Ok(SchemaValidator {
+            main,
+            plugins: compile_plugins(schema.get("plugins"))?,
+            stream_plugins: compile_plugins(schema.get("stream_plugins"))?,
+        })
+    }
+
+    pub fn from_json_str(schema: &str) -> Result<Self, SchemaError> {
+        Self::from_value(&serde_json::from_str(schema)?)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, SchemaError> {
+        Self::from_json_str(&fs::read_to_string(path)?)
+    }
+
+    pub fn validate<R>(&self, resource: &R) -> Result<(), ValidationErrors>
+    where
+        R: Resource,
+    {
+        let value = serde_json::to_value(resource).map_err(|err| {
+            ValidationErrors(vec![Violation {
+                pointer: String::new(),
+                message: err.to_string(),
+            }])
+        })?;
+        self.validate_value(R::KIND, &value)
+    }
+
+    /// Validates `value` against the core schema of `kind` and each entry of its `plugins`.
+    pub fn validate_value(
+        &self,
+        kind: ResourceKind,
+        value: &Value,
+    ) -> Result<(), ValidationErrors> {
+        let mut violations = Vec::new();
+
+        match self.main.get(kind.schema_name()) {
+            Some(validator) => violations.extend(collect(validator, value, "")),
+            None => violations.push(Violation {
+                pointer: String::new(),
+                message: format!("no schema for {}", kind.schema_name()),
+            }),
+        }
+
+        if let Some(plugins) = value.get("plugins").and_then(Value::as_object) {
+            let subsystem = match kind {
+                ResourceKind::StreamRoute => Subsystem::Stream,
+                _ => Subsystem::Http,
+            };
+            let consumer = matches!(kind, ResourceKind::Consumer | ResourceKind::Credential);
+            for (name, config) in plugins {
+                let prefix = format!("/plugins/{}", escape(name));
+                violations
+                    .extend(self.plugin_violations(subsystem, name, config, consumer, &prefix));
+            }
+        }
+
+        into_result(violations)
+    }
+
+    pub fn validate_plugin(&self, name: &str, config: &Value) -> Result<(), ValidationErrors> {
+        into_result(self.plugin_violations(Subsystem::Http, name, config, false, ""))
+    }
+
+    pub fn validate_stream_plugin(
+        &self,
+        name: &str,
+        config: &Value,
+    ) -> Result<(), ValidationErrors> {
+        into_result(self.plugin_violations(Subsystem::Stream, name, config, false, ""))
+    }
+
+    fn plugin_violations(
+        &self,
+        subsystem: Subsystem,
+        name: &str,
+        config: &Value,
+        consumer: bool,
+        prefix: &str,
+    ) -> Vec<Violation> {
+        let (plugins, others, other_subsystem) = match subsystem {
+            Subsystem::Http => (&self.plugins, &self.stream_plugins, "stream"),
+            Subsystem::Stream => (&self.stream_plugins, &self.plugins, "http"),
+        };
+        let Some(schemas) = plugins.get(name) else {
+            let message = if others.contains_key(name) {
+                format!("{name} is a {other_subsystem} only plugin")
+            } else {
+                format!("unknown plugin {name}")
+            };
+            return vec![Violation {
+                pointer: prefix.to_owned(),
+                message,
+            }];
+        };
+        let validator = match &schemas.consumer_schema {
+            Some(consumer_schema) if consumer => consumer_schema,
+            _ => &schemas.schema,
+        };
+        collect(validator, config, prefix)
+    }
+}
+
+fn compile_plugins(schemas: Option<&Value>) -> Result<HashMap<String, PluginSchemas>, SchemaError> {
+    let mut plugins = HashMap::new();
+    for (name, plugin) in schemas.and_then(Value::as_object).into_iter().flatten() {
+        let Some(schema) = plugin.get("schema") else {
+            continue;
+        };
+        let consumer_schema = plugin
+            .get("consumer_schema")
+            .map(|schema| compile(name, schema))
+            .transpose()?;
+        plugins.insert(
+            name.clone(),
+            PluginSchemas {
+                schema: compile(name, schema)?,
+                consumer_schema,
+            },
+        );
+    }
+    Ok(plugins)
+}
+
+fn compile(name: &str, schema: &Value) -> Result<Validator, SchemaError> {
+    jsonschema::draft7::new(schema).map_err(|err| SchemaError::InvalidSchema {
+        name: name.to_owned(),
+        message: err.to_string(),
+    })
+}
+
+fn collect(validator: &Validator, value: &Value, prefix: &str) -> Vec<Violation> {
+    validator
+        .iter_errors(value)
+        .map(|err| Violation {
+            pointer: format!("{prefix}{}", err.instance_path.as_str()),
+            message: err.to_string(),
+        })
+        .collect()
+}
+
+fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn into_result(violations: Vec<Violation>) -> Result<(), ValidationErrors> {
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationErrors(violations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::{Consumer, KeyAuth, LimitCount, MqttProxy, Plugins, Route, StreamRoute};
+    use serde_json::json;
+
+    fn validator() -> SchemaValidator {
+        SchemaValidator::from_value(&json!({
+            "main": {
+                "route": {
+                    "type": "object",
+                    "properties": {
+                        "uri": {"type": "string", "minLength": 1},
+                        "priority": {"type": "integer"},
+                        "plugins": {"type": "object"}
+                    },
+                    "required": ["uri"]
+                },
+                "stream_route": {
+                    "type": "object",
+                    "properties": {"server_port": {"type": "integer"}}
+                },
+                "consumer": {
+                    "type": "object",
+                    "properties": {"username": {"type": "string", "pattern": "^[a-zA-Z0-9_]+$"}},
+                    "required": ["username"]
+                }
+            },
+            "stream_plugins": {
+                "mqtt-proxy": {
+                    "schema": {
+                        "type": "object",
+                        "properties": {"protocol_level": {"type": "integer"}},
+                        "required": ["protocol_name", "protocol_level"]
+                    }
+                },
+                "limit-conn": {"schema": {"type": "object"}}
+            },
+            "plugins": {
+                "limit-conn": {"schema": {"type": "object"}},
+                "limit-count": {
+                    "schema": {
+                        "type": "object",
+                        "properties": {
+                            "count": {"type": "integer", "exclusiveMinimum": 0},
+                            "time_window": {"type": "integer", "exclusiveMinimum": 0},
+                            "rejected_code": {"type": "integer", "minimum": 200, "maximum": 599}
+                        },
+                        "required": ["count", "time_window"]
+                    }
+                },
+                "key-auth": {
+                    "schema": {
+                        "type": "object",
+                        "properties": {"header": {"type": "string"}}
+                    },
+                    "consumer_schema": {
+                        "type": "object",
+                        "properties": {"key": {"type": "string"}},
+                        "required": ["key"]
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_valid_route() {
+        let route = Route {
+            uri: Some("/orders/*".into()),
+            plugins: Plugins::new().with(LimitCount {
+                count: 10,
+                time_window: 60,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        validator().validate(&route).unwrap();
+    }
+
+    #[test]
+    fn test_reports_all_violations() {
+        let route = json!({
+            "uri": "",
+            "priority": "high",
+            "plugins": {
+                "limit-count": {"count": 0, "time_window": 60, "rejected_code": 999},
+                "no/such-plugin": {}
+            }
+        });
+
+        let ValidationErrors(violations) = validator()
+            .validate_value(ResourceKind::Route, &route)
+            .unwrap_err();
+        let mut pointers: Vec<_> = violations.iter().map(|v| v.pointer.as_str()).collect();
+        pointers.sort();
+
+        assert_eq!(
+            pointers,
+            [
+                "/plugins/limit-count/count",
+                "/plugins/limit-count/rejected_code",
+                "/plugins/no~1such-plugin",
+                "/priority",
+                "/uri",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_consumer_schema() {
+        let validator = validator();
+        let consumer = Consumer {
+            username: "partner".into(),
+            plugins: Plugins::new().with(KeyAuth::default()),
+            ..Default::default()
+        };
+
+        let err = validator.validate(&consumer).unwrap_err();
+        assert_eq!(err.0.len(), 1);
+        assert_eq!(err.0[0].pointer, "/plugins/key-auth");
+
+        validator
+            .validate_plugin("key-auth", &json!({"header": "apikey"}))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_stream_plugins() {
+        let validator = validator();
+        let stream_route = StreamRoute {
+            server_port: Some(1883),
+            plugins: Plugins::new()
+                .with(MqttProxy {
+                    protocol_name: "MQTT".into(),
+                    protocol_level: 4,
+                    ..Default::default()
+                })
+                .with(LimitCount::default()),
+            ..Default::default()
+        };
+
+        let err = validator.validate(&stream_route).unwrap_err();
+        assert_eq!(err.0.len(), 1);
+        assert_eq!(err.0[0].pointer, "/plugins/limit-count");
+        assert_eq!(err.0[0].message, "limit-count is a http only plugin");
+
+        let route = json!({"uri": "/mqtt", "plugins": {"mqtt-proxy": {}, "limit-conn": {}}});
+        let err = validator
+            .validate_value(ResourceKind::Route, &route)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "/plugins/mqtt-proxy: mqtt-proxy is a stream only plugin"
+        );
+
+        validator
+            .validate_stream_plugin("limit-conn", &json!({}))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_missing_schema() {
+        let err = validator()
+            .validate_value(ResourceKind::Upstream, &json!({}))
+            .unwrap_err();
+        assert_eq!(err.to_string(), "/: no schema for upstream");
+    }
+}
@@ -0,0 +1,351 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::{
+    ApisixConsumer, DecodeError, JwtDecodeError, MaybeApisixConsumer, MaybeXUserInfo, XAccessToken,
+    XIdToken, XUserInfo, X_ACCESS_TOKEN_HEADER, X_CONSUMER_CUSTOM_ID_HEADER,
+    X_CONSUMER_USERNAME_HEADER, X_CREDENTIAL_IDENTIFIER_HEADER, X_ID_TOKEN_HEADER,
+    X_USER_INFO_HEADER,
+};
+
+#[derive(Error, Debug)]
+pub enum XUserInfoRejection {
+    #[error("x-userinfo header is missing")]
+    MissingHeader,
+
+    #[error("invalid x-userinfo header: {0}")]
+    ToStringError(#[from] header::ToStrError),
+
+    #[error("invalid x-userinfo, base64 decode error: {0}")]
+    Base64DecodeError(#[from] base64::DecodeError),
+
+    #[error("invalid x-userinfo, json decode error: {0}")]
+    JsonDecodeError(#[from] serde_json::Error),
+}
+
+impl From<DecodeError> for XUserInfoRejection {
+    fn from(err: DecodeError) -> Self {
+        match err {
+            DecodeError::Base64(err) => Self::Base64DecodeError(err),
+            DecodeError::Json(err) => Self::JsonDecodeError(err),
+        }
+    }
+}
+
+impl IntoResponse for XUserInfoRejection {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for XUserInfo<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = XUserInfoRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = parts
+            .headers
+            .get(X_USER_INFO_HEADER)
+            .ok_or(XUserInfoRejection::MissingHeader)?
+            .to_str()?;
+
+        Ok(XUserInfo::decode(header)?)
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for MaybeXUserInfo<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = XUserInfoRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        match XUserInfo::from_request_parts(parts, state).await {
+            Ok(user_info) => Ok(Some(user_info).into()),
+            Err(XUserInfoRejection::MissingHeader) => Ok(None.into()),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum XTokenRejection {
+    #[error("{0} header is missing")]
+    MissingHeader(&'static str),
+
+    #[error("invalid {0} header: {1}")]
+    ToStringError(&'static str, header::ToStrError),
+
+    #[error("invalid {0}, {1}")]
+    DecodeError(&'static str, DecodeError),
+
+    #[error("invalid {0}, {1}")]
+    JwtDecodeError(&'static str, JwtDecodeError),
+}
+
+impl IntoResponse for XTokenRejection {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
+
+fn token_header<'a>(parts: &'a Parts, name: &'static str) -> Result<&'a str, XTokenRejection> {
+    parts
+        .headers
+        .get(name)
+        .ok_or(XTokenRejection::MissingHeader(name))?
+        .to_str()
+        .map_err(|err| XTokenRejection::ToStringError(name, err))
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for XIdToken<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = XTokenRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = token_header(parts, X_ID_TOKEN_HEADER)?;
+
+        XIdToken::decode(header).map_err(|err| XTokenRejection::DecodeError(X_ID_TOKEN_HEADER, err))
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for XAccessToken<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = XTokenRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = token_header(parts, X_ACCESS_TOKEN_HEADER)?;
+
+        XAccessToken::decode(header)
+            .map_err(|err| XTokenRejection::JwtDecodeError(X_ACCESS_TOKEN_HEADER, err))
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ApisixConsumerRejection {
+    #[error("x-consumer-username header is missing")]
+    MissingHeader,
+
+    #[error("invalid {0} header: {1}")]
+    ToStringError(&'static str, header::ToStrError),
+}
+
+impl IntoResponse for ApisixConsumerRejection {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
+
+fn consumer_header(
+    parts: &Parts,
+    name: &'static str,
+) -> Result<Option<String>, ApisixConsumerRejection> {
+    parts
+        .headers
+        .get(name)
+        .map(|value| {
+            value
+                .to_str()
+                .map(str::to_owned)
+                .map_err(|err| ApisixConsumerRejection::ToStringError(name, err))
+        })
+        .transpose()
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ApisixConsumer
+where
+    S: Send + Sync,
+{
+    type Rejection = ApisixConsumerRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        MaybeApisixConsumer::from_request_parts(parts, state)
+            .await?
+            .into_inner()
+            .ok_or(ApisixConsumerRejection::MissingHeader)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for MaybeApisixConsumer
+where
+    S: Send + Sync,
+{
+    type Rejection = ApisixConsumerRejection;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(username) = consumer_header(parts, X_CONSUMER_USERNAME_HEADER)? else {
+            return Ok(MaybeApisixConsumer(None));
+        };
+
+        Ok(MaybeApisixConsumer(Some(ApisixConsumer {
+            username,
+            credential_identifier: consumer_header(parts, X_CREDENTIAL_IDENTIFIER_HEADER)?,
+            custom_id: consumer_header(parts, X_CONSUMER_CUSTOM_ID_HEADER)?,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use base64::prelude::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize, Debug)]
+    struct CustomXUserInfo {
+        sub: String,
+        name: String,
+        iat: u64,
+    }
+
+    fn parts_with_header(value: Option<&str>) -> Parts {
+        let mut builder = Request::builder();
+        if let Some(value) = value {
+            builder = builder.header(X_USER_INFO_HEADER, value);
+        }
+        builder.body(()).unwrap().into_parts().0
+    }
+
+    #[tokio::test]
+    async fn test_x_user_info() {
+        let header_raw = json!({
+            "sub": "test sub",
+            "name": "test name",
+            "iat": 1516239022
+        });
+        let base64_encoded_header = BASE64_STANDARD.encode(header_raw.to_string().as_bytes());
+
+        let mut parts = parts_with_header(Some(&base64_encoded_header));
+        let x_user_info: XUserInfo<CustomXUserInfo> =
+            XUserInfo::from_request_parts(&mut parts, &())
+                .await
+                .unwrap();
+
+        assert_eq!(x_user_info.sub, "test sub");
+        assert_eq!(x_user_info.name, "test name");
+        assert_eq!(x_user_info.iat, 1516239022);
+    }
+
+    #[tokio::test]
+    async fn test_x_user_info_rejection() {
+        let mut parts = parts_with_header(None);
+        let rejection = XUserInfo::<CustomXUserInfo>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap_err();
+        assert!(matches!(rejection, XUserInfoRejection::MissingHeader));
+
+        let response = rejection.into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_maybe_x_user_info() {
+        let mut parts = parts_with_header(None);
+        let maybe = MaybeXUserInfo::<CustomXUserInfo>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+        assert!(maybe.is_none());
+
+        let mut parts = parts_with_header(Some("not base64!"));
+        let rejection = MaybeXUserInfo::<CustomXUserInfo>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            rejection,
+            XUserInfoRejection::Base64DecodeError(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_x_tokens() {
+        let claims = json!({"sub": "test sub", "name": "test name", "iat": 1516239022});
+        let access_token = format!(
+            "eyJhbGciOiJSUzI1NiJ9.{}.c2lnbmF0dXJl",
+            BASE64_URL_SAFE_NO_PAD.encode(claims.to_string())
+        );
+        let mut parts = Request::builder()
+            .header(X_ACCESS_TOKEN_HEADER, &access_token)
+            .header(
+                X_ID_TOKEN_HEADER,
+                BASE64_STANDARD.encode(claims.to_string()),
+            )
+            .body(())
+            .unwrap()
+            .into_parts()
+            .0;
+
+        let access: XAccessToken<CustomXUserInfo> =
+            XAccessToken::from_request_parts(&mut parts, &())
+                .await
+                .unwrap();
+        assert_eq!(access.token(), access_token);
+        assert_eq!(access.iat, 1516239022);
+
+        let id_token: XIdToken<CustomXUserInfo> =
+            XIdToken::from_request_parts(&mut parts, &()).await.unwrap();
+        assert_eq!(id_token.name, "test name");
+
+        let mut parts = parts_with_header(None);
+        let rejection = XIdToken::<CustomXUserInfo>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            rejection,
+            XTokenRejection::MissingHeader(X_ID_TOKEN_HEADER)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_apisix_consumer() {
+        let mut parts = Request::builder()
+            .header(X_CONSUMER_USERNAME_HEADER, "partner_acme")
+            .header(X_CONSUMER_CUSTOM_ID_HEADER, "acme-42")
+            .body(())
+            .unwrap()
+            .into_parts()
+            .0;
+        let consumer = ApisixConsumer::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+        assert_eq!(consumer.username, "partner_acme");
+        assert_eq!(consumer.custom_id.as_deref(), Some("acme-42"));
+
+        let mut parts = parts_with_header(None);
+        let maybe = MaybeApisixConsumer::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+        assert!(maybe.is_none());
+        let rejection = ApisixConsumer::from_request_parts(&mut parts, &())
+            .await
+            .unwrap_err();
+        assert!(matches!(rejection, ApisixConsumerRejection::MissingHeader));
+    }
+}
@@ -0,0 +1,290 @@
+use std::{collections::BTreeMap, ops::Deref};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Audience {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl Audience {
+    pub fn contains(&self, audience: &str) -> bool {
+        self.iter().any(|aud| aud == audience)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        let slice = match self {
+            Audience::Single(aud) => std::slice::from_ref(aud),
+            Audience::Multiple(auds) => auds.as_slice(),
+        };
+        slice.iter().map(String::as_str)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Address {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub street_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locality: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+}
+
+/// OpenID Connect core claims as forwarded by the `openid-connect` plugin.
+///
+/// Claims that are not part of the standard set are kept in `extra`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StandardClaims {
+    pub sub: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<Audience>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iat: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_time: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acr: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amr: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub azp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub middle_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferred_username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub picture: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gender: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub birthdate: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoneinfo: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone_number: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phone_number_verified: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<u64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Role and scope lookups used by declarative guards such as `RequireClaims`.
+pub trait RoleClaims {
+    fn has_role(&self, role: &str) -> bool;
+
+    fn has_scope(&self, scope: &str) -> bool;
+}
+
+impl RoleClaims for StandardClaims {
+    fn has_role(&self, role: &str) -> bool {
+        self.extra
+            .get("roles")
+            .and_then(Value::as_array)
+            .is_some_and(|roles| roles.iter().any(|r| r == role))
+    }
+
+    fn has_scope(&self, scope: &str) -> bool {
+        self.extra
+            .get("scope")
+            .and_then(Value::as_str)
+            .is_some_and(|scopes| scopes.split_whitespace().any(|s| s == scope))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeycloakAccess {
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+/// Keycloak token/userinfo payload with realm roles, client roles, groups and scopes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeycloakClaims {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub realm_access: Option<KeycloakAccess>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resource_access: BTreeMap<String, KeycloakAccess>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(flatten)]
+    pub standard: StandardClaims,
+}
+
+impl KeycloakClaims {
+    pub fn realm_roles(&self) -> &[String] {
+        self.realm_access
+            .as_ref()
+            .map(|access| access.roles.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn client_roles(&self, client: &str) -> &[String] {
+        self.resource_access
+            .get(client)
+            .map(|access| access.roles.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn has_realm_role(&self, role: &str) -> bool {
+        self.realm_roles().iter().any(|r| r == role)
+    }
+
+    pub fn has_client_role(&self, client: &str, role: &str) -> bool {
+        self.client_roles(client).iter().any(|r| r == role)
+    }
+
+    pub fn scopes(&self) -> impl Iterator<Item = &str> {
+        self.scope.as_deref().unwrap_or_default().split_whitespace()
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes().any(|s| s == scope)
+    }
+
+    pub fn in_group(&self, group: &str) -> bool {
+        self.groups.iter().any(|g| g == group)
+    }
+}
+
+impl RoleClaims for KeycloakClaims {
+    fn has_role(&self, role: &str) -> bool {
+        self.has_realm_role(role)
+    }
+
+    fn has_scope(&self, scope: &str) -> bool {
+        KeycloakClaims::has_scope(self, scope)
+    }
+}
+
+impl Deref for KeycloakClaims {
+    type Target = StandardClaims;
+
+    fn deref(&self) -> &Self::Target {
+        &self.standard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XUserInfo;
+    use base64::prelude::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_standard_claims() {
+        let header = BASE64_STANDARD.encode(
+            json!({
+                "sub": "248289761001",
+                "iss": "https://auth.example.com/realms/main",
+                "aud": ["account", "web"],
+                "exp": 1516242622,
+                "iat": 1516239022,
+                "email": "jane@example.com",
+                "email_verified": true,
+                "preferred_username": "jane",
+                "address": {"country": "JP"},
+                "tenant": "acme"
+            })
+            .to_string(),
+        );
+
+        let claims = XUserInfo::<StandardClaims>::decode(header).unwrap();
+        assert_eq!(claims.sub, "248289761001");
+        assert!(claims.aud.as_ref().unwrap().contains("web"));
+        assert_eq!(claims.email_verified, Some(true));
+        assert_eq!(claims.preferred_username.as_deref(), Some("jane"));
+        assert_eq!(
+            claims.address.as_ref().unwrap().country.as_deref(),
+            Some("JP")
+        );
+        assert_eq!(claims.extra["tenant"], "acme");
+        assert!(!claims.extra.contains_key("email"));
+    }
+
+    #[test]
+    fn test_audience_single() {
+        let claims: StandardClaims =
+            serde_json::from_value(json!({"sub": "1", "aud": "account"})).unwrap();
+        let aud = claims.aud.unwrap();
+        assert_eq!(aud, Audience::Single("account".into()));
+        assert!(aud.contains("account"));
+        assert_eq!(aud.iter().collect::<Vec<_>>(), ["account"]);
+    }
+
+    #[test]
+    fn test_keycloak_claims() {
+        let claims: KeycloakClaims = serde_json::from_value(json!({
+            "sub": "f1c2",
+            "preferred_username": "jane",
+            "realm_access": {"roles": ["offline_access", "admin"]},
+            "resource_access": {
+                "billing": {"roles": ["invoice:read"]},
+                "account": {"roles": ["manage-account"]}
+            },
+            "groups": ["/staff"],
+            "scope": "openid profile email",
+            "tenant": "acme"
+        }))
+        .unwrap();
+
+        assert_eq!(claims.preferred_username.as_deref(), Some("jane"));
+        assert!(claims.has_realm_role("admin"));
+        assert!(!claims.has_realm_role("invoice:read"));
+        assert!(claims.has_client_role("billing", "invoice:read"));
+        assert!(!claims.has_client_role("account", "invoice:read"));
+        assert!(!claims.has_client_role("missing", "admin"));
+        assert_eq!(
+            claims.scopes().collect::<Vec<_>>(),
+            ["openid", "profile", "email"]
+        );
+        assert!(claims.has_scope("email"));
+        assert!(claims.in_group("/staff"));
+        assert_eq!(claims.standard.extra.len(), 1);
+        assert_eq!(claims.standard.extra["tenant"], "acme");
+    }
+}
@@ -0,0 +1,282 @@
+use std::{borrow::Cow, sync::Arc};
+
+use actix_web::{
+    error::InternalError,
+    http::{header::ContentType, StatusCode},
+    web, HttpRequest, HttpResponse,
+};
+use serde_json::json;
+
+use crate::{Base64Alphabet, RequireClaimsError, XUserInfoError, X_USER_INFO_HEADER};
+
+type ErrorHandler = Arc<dyn Fn(XUserInfoError, &HttpRequest) -> actix_web::Error + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    PlainText,
+    /// RFC 7807 `application/problem+json` body.
+    ProblemJson,
+}
+
+/// Extractor configuration for `XUserInfo`, `MaybeXUserInfo` and `RequireClaims`, registered
+/// via `app_data`.
+///
+/// ```no_run
+/// use actix_web::{http::StatusCode, App};
+/// use apisix_rs::{Base64Alphabet, ErrorFormat, XUserInfoConfig};
+///
+/// let app = App::new().app_data(
+///     XUserInfoConfig::default()
+///         .header_name("x-user")
+///         .alphabets([Base64Alphabet::Standard, Base64Alphabet::UrlSafeNoPad])
+///         .missing_header_status(StatusCode::UNAUTHORIZED)
+///         .error_format(ErrorFormat::ProblemJson),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct XUserInfoConfig {
+    header_name: Cow<'static, str>,
+    alphabets: Cow<'static, [Base64Alphabet]>,
+    missing_header_status: Option<StatusCode>,
+    invalid_header_status: StatusCode,
+    base64_error_status: StatusCode,
+    json_error_status: StatusCode,
+    insufficient_claims_status: StatusCode,
+    error_format: ErrorFormat,
+    error_handler: Option<ErrorHandler>,
+}
+
+static DEFAULT_CONFIG: XUserInfoConfig = XUserInfoConfig {
+    header_name: Cow::Borrowed(X_USER_INFO_HEADER),
+    alphabets: Cow::Borrowed(&[Base64Alphabet::Standard]),
+    missing_header_status: None,
+    invalid_header_status: StatusCode::BAD_REQUEST,
+    base64_error_status: StatusCode::BAD_REQUEST,
+    json_error_status: StatusCode::BAD_REQUEST,
+    insufficient_claims_status: StatusCode::FORBIDDEN,
+    error_format: ErrorFormat::PlainText,
+    error_handler: None,
+};
+
+impl Default for XUserInfoConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG.clone()
+    }
+}
+
+impl XUserInfoConfig {
+    pub fn header_name(mut self, header_name: impl Into<Cow<'static, str>>) -> Self {
+        self.header_name = header_name.into();
+        self
+    }
+
+    pub fn alphabets(mut self, alphabets: impl IntoIterator<Item = Base64Alphabet>) -> Self {
+        self.alphabets = Cow::Owned(alphabets.into_iter().collect());
+        self
+    }
+
+    /// Defaults to 400 for the extractors and 401 for [`RequireClaims`](crate::RequireClaims).
+    pub fn missing_header_status(mut self, status: StatusCode) -> Self {
+        self.missing_header_status = Some(status);
+        self
+    }
+
+    pub fn invalid_header_status(mut self, status: StatusCode) -> Self {
+        self.invalid_header_status = status;
+        self
+    }
+
+    pub fn base64_error_status(mut self, status: StatusCode) -> Self {
+        self.base64_error_status = status;
+        self
+    }
+
+    pub fn json_error_status(mut self, status: StatusCode) -> Self {
+        self.json_error_status = status;
+        self
+    }
+
+    /// Status used by [`RequireClaims`](crate::RequireClaims) when the predicate fails.
+    pub fn insufficient_claims_status(mut self, status: StatusCode) -> Self {
+        self.insufficient_claims_status = status;
+        self
+    }
+
+    pub fn error_format(mut self, error_format: ErrorFormat) -> Self {
+        self.error_format = error_format;
+        self
+    }
+
+    /// Replaces the built-in error response; status codes and error format are then ignored.
+    pub fn error_handler<F>(mut self, error_handler: F) -> Self
+    where
+        F: Fn(XUserInfoError, &HttpRequest) -> actix_web::Error + Send + Sync + 'static,
+    {
+        self.error_handler = Some(Arc::new(error_handler));
+        self
+    }
+
+    pub(crate) fn from_req(req: &HttpRequest) -> &Self {
+        req.app_data::<Self>()
+            .or_else(|| req.app_data::<web::Data<Self>>().map(|data| data.as_ref()))
+            .unwrap_or(&DEFAULT_CONFIG)
+    }
+
+    pub(crate) fn get_header_name(&self) -> &str {
+        &self.header_name
+    }
+
+    pub(crate) fn get_alphabets(&self) -> &[Base64Alphabet] {
+        &self.alphabets
+    }
+
+    pub(crate) fn status_code(&self, err: &XUserInfoError) -> StatusCode {
+        match err {
+            XUserInfoError::MissingHeader => self
+                .missing_header_status
+                .unwrap_or(StatusCode::BAD_REQUEST),
+            XUserInfoError::ToStringError(_) => self.invalid_header_status,
+            XUserInfoError::Base64DecodeError(_) => self.base64_error_status,
+            XUserInfoError::JsonDecodeError(_) => self.json_error_status,
+        }
+    }
+
+    pub(crate) fn error(&self, err: XUserInfoError, req: &HttpRequest) -> actix_web::Error {
+        if let Some(error_handler) = &self.error_handler {
+            return error_handler(err, req);
+        }
+
+        let status = self.status_code(&err);
+        self.respond(err, status)
+    }
+
+    /// Like [`XUserInfoConfig::error`], but a missing header is a 401 unless configured
+    /// otherwise. The error handler only sees the header errors.
+    pub(crate) fn require_claims_error(
+        &self,
+        err: RequireClaimsError,
+        req: &HttpRequest,
+    ) -> actix_web::Error {
+        let err = match err {
+            RequireClaimsError::UserInfo(err) if self.error_handler.is_some() => {
+                return self.error(err, req)
+            }
+            err => err,
+        };
+        let status = match &err {
+            RequireClaimsError::UserInfo(XUserInfoError::MissingHeader) => self
+                .missing_header_status
+                .unwrap_or(StatusCode::UNAUTHORIZED),
+            RequireClaimsError::UserInfo(err) => self.status_code(err),
+            RequireClaimsError::InsufficientClaims => self.insufficient_claims_status,
+        };
+        self.respond(err, status)
+    }
+
+    fn respond<E>(&self, err: E, status: StatusCode) -> actix_web::Error
+    where
+        E: std::fmt::Debug + std::fmt::Display + 'static,
+    {
+        let response = match self.error_format {
+            ErrorFormat::PlainText => HttpResponse::build(status)
+                .insert_header(ContentType::plaintext())
+                .body(err.to_string()),
+            ErrorFormat::ProblemJson => HttpResponse::build(status)
+                .content_type("application/problem+json")
+                .body(
+                    json!({
+                        "type": "about:blank",
+                        "title": status.canonical_reason().unwrap_or_default(),
+                        "status": status.as_u16(),
+                        "detail": err.to_string(),
+                    })
+                    .to_string(),
+                ),
+        };
+
+        InternalError::from_response(err, response).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MaybeXUserInfo, StandardClaims, XUserInfo};
+    use actix_web::{body::to_bytes, test::TestRequest, FromRequest};
+    use base64::prelude::*;
+
+    #[actix_rt::test]
+    async fn test_default_config() {
+        let req = TestRequest::default().to_http_request();
+        let err = XUserInfo::<StandardClaims>::extract(&req)
+            .await
+            .unwrap_err();
+        let response = err.error_response();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            to_bytes(response.into_body()).await.unwrap(),
+            "x-userinfo header is missing"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_custom_config() {
+        let config = XUserInfoConfig::default()
+            .header_name("x-user")
+            .alphabets([Base64Alphabet::UrlSafeNoPad])
+            .missing_header_status(StatusCode::UNAUTHORIZED)
+            .error_format(ErrorFormat::ProblemJson);
+
+        let header = BASE64_URL_SAFE_NO_PAD.encode(r#"{"sub":"jane"}"#);
+        let req = TestRequest::default()
+            .app_data(config.clone())
+            .insert_header(("x-user", header))
+            .to_http_request();
+        let user_info = XUserInfo::<StandardClaims>::extract(&req).await.unwrap();
+        assert_eq!(user_info.sub, "jane");
+
+        let req = TestRequest::default()
+            .app_data(web::Data::new(config))
+            .insert_header((
+                X_USER_INFO_HEADER,
+                BASE64_STANDARD.encode(r#"{"sub":"jane"}"#),
+            ))
+            .to_http_request();
+        assert!(MaybeXUserInfo::<StandardClaims>::extract(&req)
+            .await
+            .unwrap()
+            .is_none());
+
+        let err = XUserInfo::<StandardClaims>::extract(&req)
+            .await
+            .unwrap_err();
+        let response = err.error_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/problem+json"
+        );
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap();
+        assert_eq!(body["status"], 401);
+        assert_eq!(body["detail"], "x-userinfo header is missing");
+    }
+
+    #[actix_rt::test]
+    async fn test_error_handler() {
+        let config = XUserInfoConfig::default().error_handler(|err, _req| {
+            let response = HttpResponse::ImATeapot().finish();
+            InternalError::from_response(err, response).into()
+        });
+
+        let req = TestRequest::default()
+            .app_data(config)
+            .insert_header((X_USER_INFO_HEADER, "%%%"))
+            .to_http_request();
+        let err = MaybeXUserInfo::<StandardClaims>::extract(&req)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::IM_A_TEAPOT);
+    }
+}
@@ -0,0 +1,37 @@
+use std::ops::Deref;
+
+/// Consumer identity forwarded by APISIX after key-auth, basic-auth, hmac-auth or jwt-auth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApisixConsumer {
+    pub username: String,
+    pub credential_identifier: Option<String>,
+    pub custom_id: Option<String>,
+}
+
+impl ApisixConsumer {
+    pub fn new(username: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            credential_identifier: None,
+            custom_id: None,
+        }
+    }
+}
+
+/// Like [`ApisixConsumer`], but yields `None` when the request carries no consumer headers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaybeApisixConsumer(pub(crate) Option<ApisixConsumer>);
+
+impl MaybeApisixConsumer {
+    pub fn into_inner(self) -> Option<ApisixConsumer> {
+        self.0
+    }
+}
+
+impl Deref for MaybeApisixConsumer {
+    type Target = Option<ApisixConsumer>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
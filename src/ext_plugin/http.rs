@@ -0,0 +1,554 @@
+use std::{io, net::IpAddr};
+
+use thiserror::Error;
+
+use super::{
+    proto::{self, text_entries, HttpReqCallReq, HttpRespCallReq, Info},
+    Conn,
+};
+
+type Entries = Vec<(String, Option<String>)>;
+
+#[derive(Error, Debug)]
+pub enum ExtraInfoError {
+    #[error("extra info can only be queried while APISIX waits for the call")]
+    Unavailable,
+
+    #[error("extra info io error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid extra info response")]
+    InvalidResponse,
+}
+
+/// `A6.Method`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Head,
+    Post,
+    Put,
+    Delete,
+    Mkcol,
+    Copy,
+    Move,
+    Options,
+    Propfind,
+    Proppatch,
+    Lock,
+    Unlock,
+    Patch,
+    Trace,
+}
+
+impl Method {
+    fn from_u8(method: u8) -> Option<Self> {
+        const METHODS: [Method; 15] = [
+            Method::Get,
+            Method::Head,
+            Method::Post,
+            Method::Put,
+            Method::Delete,
+            Method::Mkcol,
+            Method::Copy,
+            Method::Move,
+            Method::Options,
+            Method::Propfind,
+            Method::Proppatch,
+            Method::Lock,
+            Method::Unlock,
+            Method::Patch,
+            Method::Trace,
+        ];
+        METHODS.get(method as usize).copied()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Head => "HEAD",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Delete => "DELETE",
+            Method::Mkcol => "MKCOL",
+            Method::Copy => "COPY",
+            Method::Move => "MOVE",
+            Method::Options => "OPTIONS",
+            Method::Propfind => "PROPFIND",
+            Method::Proppatch => "PROPPATCH",
+            Method::Lock => "LOCK",
+            Method::Unlock => "UNLOCK",
+            Method::Patch => "PATCH",
+            Method::Trace => "TRACE",
+        }
+    }
+}
+
+fn find<'a>(entries: &'a [(String, Option<String>)], name: &str) -> Option<&'a str> {
+    entries
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.as_deref())
+}
+
+fn iter(entries: &[(String, Option<String>)]) -> impl Iterator<Item = (&str, &str)> {
+    entries
+        .iter()
+        .filter_map(|(key, value)| Some((key.as_str(), value.as_deref()?)))
+}
+
+/// Replaces every entry called `name`, or removes them when `value` is `None`.
+fn upsert(entries: &mut Entries, name: &str, value: Option<&str>, ignore_case: bool) {
+    entries.retain(|(key, _)| {
+        if ignore_case {
+            !key.eq_ignore_ascii_case(name)
+        } else {
+            key != name
+        }
+    });
+    entries.push((name.to_owned(), value.map(str::to_owned)));
+}
+
+async fn extra_info(conn: Option<&Conn>, info: Info<'_>) -> Result<Vec<u8>, ExtraInfoError> {
+    conn.ok_or(ExtraInfoError::Unavailable)?
+        .extra_info(info)
+        .await
+}
+
+/// A response sent to the client instead of proxying the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stop {
+    status: u16,
+    headers: Entries,
+    body: Vec<u8>,
+}
+
+impl Stop {
+    pub fn new(status: u16) -> Self {
+        Stop {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), Some(value.into())));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Rewrite {
+    path: Option<String>,
+    headers: Entries,
+    args: Entries,
+    resp_headers: Entries,
+}
+
+impl Rewrite {
+    fn is_empty(&self) -> bool {
+        self.path.is_none()
+            && self.headers.is_empty()
+            && self.args.is_empty()
+            && self.resp_headers.is_empty()
+    }
+}
+
+/// The request APISIX hands to [`Plugin::filter`](super::Plugin::filter).
+///
+/// Changes are sent back to APISIX once every plugin has run: a [`Stop`] answers the client
+/// directly, anything else rewrites the request before it is proxied.
+#[derive(Debug, Clone)]
+pub struct Request {
+    id: u32,
+    src_ip: Option<IpAddr>,
+    method: Option<Method>,
+    path: String,
+    args: Entries,
+    headers: Entries,
+    rewrite: Rewrite,
+    stop: Option<Stop>,
+    conn: Option<Conn>,
+}
+
+impl Request {
+    pub(crate) fn from_call(call: &HttpReqCallReq<'_>, conn: Option<Conn>) -> Self {
+        let src_ip = match call.src_ip() {
+            &[a, b, c, d] => Some(IpAddr::from([a, b, c, d])),
+            ip => <[u8; 16]>::try_from(ip).ok().map(IpAddr::from),
+        };
+
+        Request {
+            id: call.id(),
+            src_ip,
+            method: Method::from_u8(call.method()),
+            path: call.path().to_owned(),
+            args: text_entries(call.args()),
+            headers: text_entries(call.headers()),
+            rewrite: Rewrite::default(),
+            stop: None,
+            conn,
+        }
+    }
+
+    /// The id APISIX assigned to this call.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn src_ip(&self) -> Option<IpAddr> {
+        self.src_ip
+    }
+
+    pub fn method(&self) -> Option<Method> {
+        self.method
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .find(|(key, _)| key == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    pub fn args(&self) -> impl Iterator<Item = (&str, &str)> {
+        iter(&self.args)
+    }
+
+    /// Looks a header up case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find(&self.headers, name)
+    }
+
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        iter(&self.headers)
+    }
+
+    /// Rewrites the path proxied upstream. It is not escaped by APISIX.
+    pub fn set_path(&mut self, path: impl Into<String>) {
+        self.path = path.into();
+        self.rewrite.path = Some(self.path.clone());
+    }
+
+    pub fn set_arg(&mut self, name: &str, value: &str) {
+        self.change_arg(name, Some(value));
+    }
+
+    pub fn remove_arg(&mut self, name: &str) {
+        self.change_arg(name, None);
+    }
+
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        self.change_header(name, Some(value));
+    }
+
+    pub fn remove_header(&mut self, name: &str) {
+        self.change_header(name, None);
+    }
+
+    /// Sets a header on the response once the upstream answers.
+    pub fn set_response_header(&mut self, name: &str, value: &str) {
+        upsert(&mut self.rewrite.resp_headers, name, Some(value), true);
+    }
+
+    /// Answers the client with `stop`; plugins after this one are skipped.
+    pub fn stop(&mut self, stop: Stop) {
+        self.stop = Some(stop);
+    }
+
+    pub fn stopped(&self) -> Option<&Stop> {
+        self.stop.as_ref()
+    }
+
+    /// Fetches an nginx variable, e.g. `remote_addr`, from APISIX.
+    pub async fn var(&self, name: &str) -> Result<Vec<u8>, ExtraInfoError> {
+        extra_info(self.conn.as_ref(), Info::Var(name)).await
+    }
+
+    /// Fetches the request body from APISIX.
+    pub async fn read_body(&self) -> Result<Vec<u8>, ExtraInfoError> {
+        extra_info(self.conn.as_ref(), Info::ReqBody).await
+    }
+
+    fn change_arg(&mut self, name: &str, value: Option<&str>) {
+        self.args.retain(|(key, _)| key != name);
+        if let Some(value) = value {
+            self.args.push((name.to_owned(), Some(value.to_owned())));
+        }
+        upsert(&mut self.rewrite.args, name, value, false);
+    }
+
+    fn change_header(&mut self, name: &str, value: Option<&str>) {
+        self.headers
+            .retain(|(key, _)| !key.eq_ignore_ascii_case(name));
+        if let Some(value) = value {
+            self.headers.push((name.to_owned(), Some(value.to_owned())));
+        }
+        upsert(&mut self.rewrite.headers, name, value, true);
+    }
+
+    /// Encodes the `HTTPReqCall.Resp` carrying the changes made by the plugins.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        if let Some(stop) = &self.stop {
+            return proto::http_req_call_stop(self.id, stop.status, &stop.headers, &stop.body);
+        }
+        if self.rewrite.is_empty() {
+            return proto::http_req_call_resp(self.id);
+        }
+        proto::http_req_call_rewrite(
+            self.id,
+            self.rewrite.path.as_deref(),
+            &self.rewrite.headers,
+            &self.rewrite.args,
+            &self.rewrite.resp_headers,
+        )
+    }
+}
+
+/// The upstream response APISIX hands to [`Plugin::response_filter`](super::Plugin::response_filter).
+#[derive(Debug, Clone)]
+pub struct Response {
+    id: u32,
+    status: u16,
+    headers: Entries,
+    new_status: Option<u16>,
+    new_headers: Entries,
+    body: Option<Vec<u8>>,
+    conn: Option<Conn>,
+}
+
+impl Response {
+    pub(crate) fn from_call(call: &HttpRespCallReq<'_>, conn: Option<Conn>) -> Self {
+        Response {
+            id: call.id(),
+            status: call.status(),
+            headers: text_entries(call.headers()),
+            new_status: None,
+            new_headers: Vec::new(),
+            body: None,
+            conn,
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    /// Looks a header up case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find(&self.headers, name)
+    }
+
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        iter(&self.headers)
+    }
+
+    pub fn set_status(&mut self, status: u16) {
+        self.status = status;
+        self.new_status = Some(status);
+    }
+
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        self.change_header(name, Some(value));
+    }
+
+    pub fn remove_header(&mut self, name: &str) {
+        self.change_header(name, None);
+    }
+
+    /// Replaces the body sent to the client.
+    pub fn set_body(&mut self, body: impl Into<Vec<u8>>) {
+        self.body = Some(body.into());
+    }
+
+    /// Fetches an nginx variable, e.g. `upstream_addr`, from APISIX.
+    pub async fn var(&self, name: &str) -> Result<Vec<u8>, ExtraInfoError> {
+        extra_info(self.conn.as_ref(), Info::Var(name)).await
+    }
+
+    /// Fetches the upstream response body from APISIX.
+    pub async fn read_body(&self) -> Result<Vec<u8>, ExtraInfoError> {
+        extra_info(self.conn.as_ref(), Info::RespBody).await
+    }
+
+    fn change_header(&mut self, name: &str, value: Option<&str>) {
+        self.headers
+            .retain(|(key, _)| !key.eq_ignore_ascii_case(name));
+        if let Some(value) = value {
+            self.headers.push((name.to_owned(), Some(value.to_owned())));
+        }
+        upsert(&mut self.new_headers, name, value, true);
+    }
+
+    /// Encodes the `HTTPRespCall.Resp` carrying the changes made by the plugins.
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        proto::http_resp_call_resp(
+            self.id,
+            self.new_status.unwrap_or_default(),
+            &self.new_headers,
+            self.body.as_deref(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext_plugin::proto::{
+        apisix::{self, ReqCall},
+        HttpReqCallResp, HttpRespCallResp, ACTION_NONE,
+    };
+
+    fn entries(entries: Option<proto::TextEntries<'_>>) -> Entries {
+        text_entries(entries)
+    }
+
+    fn request() -> Request {
+        let call = apisix::http_req_call_req(&ReqCall {
+            id: 5,
+            conf_token: 1,
+            src_ip: &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            method: 0,
+            path: "/v1/orders",
+            args: &[("page", "2"), ("debug", "1")],
+            headers: &[("Host", "api.example.com"), ("X-Internal", "yes")],
+        });
+        Request::from_call(&flatbuffers::root::<HttpReqCallReq>(&call).unwrap(), None)
+    }
+
+    fn response() -> Response {
+        let call = apisix::http_resp_call_req(6, 1, 200, &[("Content-Type", "text/plain")]);
+        Response::from_call(&flatbuffers::root::<HttpRespCallReq>(&call).unwrap(), None)
+    }
+
+    #[test]
+    fn test_request() {
+        let request = request();
+        assert_eq!(request.src_ip(), Some("::1".parse().unwrap()));
+        assert_eq!(request.method(), Some(Method::Get));
+        assert_eq!(request.arg("page"), Some("2"));
+        assert_eq!(request.header("host"), Some("api.example.com"));
+
+        let buf = request.encode();
+        let resp = flatbuffers::root::<HttpReqCallResp>(&buf).unwrap();
+        assert_eq!(resp.id(), 5);
+        assert_eq!(resp.action_type(), ACTION_NONE);
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let mut request = request();
+        request.set_path("/orders");
+        request.set_arg("page", "3");
+        request.remove_arg("debug");
+        request.set_header("X-Tenant", "acme");
+        request.remove_header("x-internal");
+        request.set_response_header("Cache-Control", "no-store");
+
+        assert_eq!(request.path(), "/orders");
+        assert_eq!(request.args().collect::<Vec<_>>(), [("page", "3")]);
+        assert_eq!(request.header("x-tenant"), Some("acme"));
+        assert_eq!(request.header("x-internal"), None);
+
+        let buf = request.encode();
+        let resp = flatbuffers::root::<HttpReqCallResp>(&buf).unwrap();
+        let rewrite = resp.action_as_rewrite().unwrap();
+        assert!(resp.action_as_stop().is_none());
+        assert_eq!(rewrite.path(), Some("/orders"));
+        assert_eq!(
+            entries(rewrite.args()),
+            [("page".into(), Some("3".into())), ("debug".into(), None)]
+        );
+        assert_eq!(
+            entries(rewrite.headers()),
+            [
+                ("X-Tenant".into(), Some("acme".into())),
+                ("x-internal".into(), None)
+            ]
+        );
+        assert_eq!(
+            entries(rewrite.resp_headers()),
+            [("Cache-Control".into(), Some("no-store".into()))]
+        );
+    }
+
+    #[test]
+    fn test_stop() {
+        let mut request = request();
+        request.set_header("X-Tenant", "acme");
+        request.stop(
+            Stop::new(401)
+                .header("WWW-Authenticate", "Bearer")
+                .body("unauthorized"),
+        );
+        assert_eq!(request.stopped().map(Stop::status), Some(401));
+
+        let buf = request.encode();
+        let resp = flatbuffers::root::<HttpReqCallResp>(&buf).unwrap();
+        let stop = resp.action_as_stop().unwrap();
+        assert!(resp.action_as_rewrite().is_none());
+        assert_eq!(stop.status(), 401);
+        assert_eq!(
+            entries(stop.headers()),
+            [("WWW-Authenticate".into(), Some("Bearer".into()))]
+        );
+        assert_eq!(stop.body(), Some(&b"unauthorized"[..]));
+    }
+
+    #[test]
+    fn test_response() {
+        let response = response();
+        let buf = response.encode();
+        let resp = flatbuffers::root::<HttpRespCallResp>(&buf).unwrap();
+        assert_eq!(resp.id(), 6);
+        assert_eq!(resp.status(), 0);
+        assert!(resp.headers().is_none());
+        assert!(resp.body().is_none());
+
+        let mut response = response;
+        response.set_status(502);
+        response.set_header("X-Upstream", "orders");
+        response.remove_header("content-type");
+        response.set_body("bad gateway");
+        assert_eq!(response.status(), 502);
+        assert_eq!(response.header("Content-Type"), None);
+
+        let buf = response.encode();
+        let resp = flatbuffers::root::<HttpRespCallResp>(&buf).unwrap();
+        assert_eq!(resp.status(), 502);
+        assert_eq!(
+            entries(resp.headers()),
+            [
+                ("X-Upstream".into(), Some("orders".into())),
+                ("content-type".into(), None)
+            ]
+        );
+        assert_eq!(resp.body(), Some(&b"bad gateway"[..]));
+    }
+
+    #[tokio::test]
+    async fn test_extra_info_without_connection() {
+        let err = request().var("remote_addr").await.unwrap_err();
+        assert!(matches!(err, ExtraInfoError::Unavailable));
+        let err = response().read_body().await.unwrap_err();
+        assert!(matches!(err, ExtraInfoError::Unavailable));
+    }
+}
@@ -0,0 +1,90 @@
+//! A runner for APISIX external plugins, speaking the ext-plugin RPC over a Unix socket.
+//!
+//! APISIX starts the runner with `APISIX_LISTEN_ADDRESS` set and forwards calls of the
+//! `ext-plugin-pre-req`, `ext-plugin-post-req` and `ext-plugin-post-resp` plugins to it.
+
+mod http;
+mod proto;
+mod runner;
+
+use std::{io, sync::Arc};
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::UnixStream,
+    sync::Mutex,
+};
+
+use proto::{ExtraInfoResp, Info};
+
+pub use async_trait::async_trait;
+pub use http::{ExtraInfoError, Method, Request, Response, Stop};
+pub use runner::{BoxError, Plugin, Runner, RunnerError};
+
+/// Bodies are framed with a 1 byte message type and a 3 byte big-endian length.
+const MAX_FRAME_LEN: usize = 0xFF_FFFF;
+
+pub(crate) async fn read_frame<R>(reader: &mut R) -> io::Result<(u8, Vec<u8>)>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut header = [0; 4];
+    reader.read_exact(&mut header).await?;
+    let len = u32::from_be_bytes([0, header[1], header[2], header[3]]) as usize;
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body).await?;
+    Ok((header[0], body))
+}
+
+pub(crate) async fn write_frame<W>(writer: &mut W, ty: u8, body: &[u8]) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    if body.len() > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "ext-plugin frame exceeds 16 MiB",
+        ));
+    }
+
+    let len = (body.len() as u32).to_be_bytes();
+    writer.write_all(&[ty, len[1], len[2], len[3]]).await?;
+    writer.write_all(body).await?;
+    writer.flush().await
+}
+
+/// A connection from APISIX, shared with the call being served so it can query extra info.
+#[derive(Debug, Clone)]
+pub(crate) struct Conn(Arc<Mutex<UnixStream>>);
+
+impl Conn {
+    pub(crate) fn new(stream: UnixStream) -> Self {
+        Conn(Arc::new(Mutex::new(stream)))
+    }
+
+    pub(crate) async fn read(&self) -> io::Result<(u8, Vec<u8>)> {
+        read_frame(&mut *self.0.lock().await).await
+    }
+
+    pub(crate) async fn write(&self, ty: u8, body: &[u8]) -> io::Result<()> {
+        write_frame(&mut *self.0.lock().await, ty, body).await
+    }
+
+    /// APISIX answers `ExtraInfo` requests on the same connection while it waits for the call.
+    pub(crate) async fn extra_info(&self, info: Info<'_>) -> Result<Vec<u8>, ExtraInfoError> {
+        let mut stream = self.0.lock().await;
+        write_frame(
+            &mut *stream,
+            proto::RPC_EXTRA_INFO,
+            &proto::extra_info_req(info),
+        )
+        .await?;
+        let (ty, body) = read_frame(&mut *stream).await?;
+        if ty != proto::RPC_EXTRA_INFO {
+            return Err(ExtraInfoError::InvalidResponse);
+        }
+        let resp = flatbuffers::root::<ExtraInfoResp>(&body)
+            .map_err(|_| ExtraInfoError::InvalidResponse)?;
+        Ok(resp.result().to_vec())
+    }
+}
@@ -0,0 +1,728 @@
+//! Hand-written flatbuffers accessors for the A6 schemas of the ext-plugin protocol
+//! (<https://github.com/api7/ext-plugin-proto>).
+//!
+//! Readers are only constructed through [`flatbuffers::root`], which verifies the buffer,
+//! so the unchecked table accesses below stay in bounds.
+
+use flatbuffers::{
+    FlatBufferBuilder, Follow, ForwardsUOffset, InvalidFlatbuffer, Table, VOffsetT, Vector,
+    Verifiable, Verifier, WIPOffset,
+};
+
+pub(crate) const RPC_ERROR: u8 = 0;
+pub(crate) const RPC_PREPARE_CONF: u8 = 1;
+pub(crate) const RPC_HTTP_REQ_CALL: u8 = 2;
+pub(crate) const RPC_EXTRA_INFO: u8 = 3;
+pub(crate) const RPC_HTTP_RESP_CALL: u8 = 4;
+
+/// `A6.Err.Code`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub(crate) enum ErrorCode {
+    BadRequest = 0,
+    ServiceUnavailable = 1,
+    ConfTokenNotFound = 2,
+}
+
+/// `A6.HTTPReqCall.Action`
+pub(crate) const ACTION_NONE: u8 = 0;
+pub(crate) const ACTION_STOP: u8 = 1;
+pub(crate) const ACTION_REWRITE: u8 = 2;
+
+/// `A6.ExtraInfo.Info`
+pub(crate) const INFO_VAR: u8 = 1;
+pub(crate) const INFO_REQ_BODY: u8 = 2;
+pub(crate) const INFO_RESP_BODY: u8 = 3;
+
+/// An `A6.ExtraInfo.Req` query.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Info<'a> {
+    Var(&'a str),
+    ReqBody,
+    RespBody,
+}
+
+pub(crate) type TextEntries<'a> = Vector<'a, ForwardsUOffset<TextEntry<'a>>>;
+
+/// Vtable offset of the `n`-th field of a table.
+const fn field(n: VOffsetT) -> VOffsetT {
+    4 + 2 * n
+}
+
+macro_rules! table {
+    ($($name:ident),* $(,)?) => {
+        $(
+            #[derive(Clone, Copy)]
+            pub(crate) struct $name<'a>(Table<'a>);
+
+            impl<'a> Follow<'a> for $name<'a> {
+                type Inner = $name<'a>;
+
+                unsafe fn follow(buf: &'a [u8], loc: usize) -> Self::Inner {
+                    $name(Table::new(buf, loc))
+                }
+            }
+
+            impl<'a> $name<'a> {
+                #[allow(dead_code)]
+                fn scalar<T>(&self, n: VOffsetT, default: T) -> T
+                where
+                    T: Follow<'a, Inner = T> + Copy + 'a,
+                {
+                    // SAFETY: the buffer was verified against this table's schema.
+                    unsafe { self.0.get::<T>(field(n), Some(default)).unwrap_or(default) }
+                }
+
+                #[allow(dead_code)]
+                fn offset<T>(&self, n: VOffsetT) -> Option<T::Inner>
+                where
+                    T: Follow<'a> + 'a,
+                {
+                    // SAFETY: the buffer was verified against this table's schema.
+                    unsafe { self.0.get::<ForwardsUOffset<T>>(field(n), None) }
+                }
+            }
+        )*
+    };
+}
+
+table! {
+    TextEntry,
+    PrepareConfReq,
+    HttpReqCallReq,
+    HttpRespCallReq,
+    ExtraInfoResp,
+}
+
+// Responses are only read back by the fake APISIX in tests.
+#[cfg(test)]
+table! {
+    ErrResp,
+    PrepareConfResp,
+    HttpReqCallResp,
+    HttpRespCallResp,
+    Stop,
+    Rewrite,
+    ExtraInfoReq,
+    ExtraInfoVar,
+}
+
+/// `A6.TextEntry { name: string; value: string; }`
+impl<'a> TextEntry<'a> {
+    pub(crate) fn name(&self) -> &'a str {
+        self.offset::<&str>(0).unwrap_or_default()
+    }
+
+    pub(crate) fn value(&self) -> Option<&'a str> {
+        self.offset::<&str>(1)
+    }
+}
+
+impl Verifiable for TextEntry<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<ForwardsUOffset<&str>>("name", field(0), false)?
+            .visit_field::<ForwardsUOffset<&str>>("value", field(1), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.Err.Resp { code: Code; }`
+#[cfg(test)]
+impl ErrResp<'_> {
+    pub(crate) fn code(&self) -> u32 {
+        self.scalar(0, 0)
+    }
+}
+
+#[cfg(test)]
+impl Verifiable for ErrResp<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<u32>("code", field(0), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.PrepareConf.Req { conf: [TextEntry]; key: string; }`
+impl<'a> PrepareConfReq<'a> {
+    pub(crate) fn conf(&self) -> Option<TextEntries<'a>> {
+        self.offset::<TextEntries>(0)
+    }
+
+    pub(crate) fn key(&self) -> &'a str {
+        self.offset::<&str>(1).unwrap_or_default()
+    }
+}
+
+impl Verifiable for PrepareConfReq<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<ForwardsUOffset<TextEntries>>("conf", field(0), false)?
+            .visit_field::<ForwardsUOffset<&str>>("key", field(1), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.PrepareConf.Resp { conf_token: uint32; }`
+#[cfg(test)]
+impl PrepareConfResp<'_> {
+    pub(crate) fn conf_token(&self) -> u32 {
+        self.scalar(0, 0)
+    }
+}
+
+#[cfg(test)]
+impl Verifiable for PrepareConfResp<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<u32>("conf_token", field(0), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.HTTPReqCall.Req { id; src_ip: [ubyte]; method: Method; path; args; headers; conf_token; }`
+impl<'a> HttpReqCallReq<'a> {
+    pub(crate) fn id(&self) -> u32 {
+        self.scalar(0, 0)
+    }
+
+    pub(crate) fn src_ip(&self) -> &'a [u8] {
+        self.offset::<Vector<u8>>(1)
+            .map(|ip| ip.bytes())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn method(&self) -> u8 {
+        self.scalar(2, 0)
+    }
+
+    pub(crate) fn path(&self) -> &'a str {
+        self.offset::<&str>(3).unwrap_or_default()
+    }
+
+    pub(crate) fn args(&self) -> Option<TextEntries<'a>> {
+        self.offset::<TextEntries>(4)
+    }
+
+    pub(crate) fn headers(&self) -> Option<TextEntries<'a>> {
+        self.offset::<TextEntries>(5)
+    }
+
+    pub(crate) fn conf_token(&self) -> u32 {
+        self.scalar(6, 0)
+    }
+}
+
+impl Verifiable for HttpReqCallReq<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<u32>("id", field(0), false)?
+            .visit_field::<ForwardsUOffset<Vector<u8>>>("src_ip", field(1), false)?
+            .visit_field::<u8>("method", field(2), false)?
+            .visit_field::<ForwardsUOffset<&str>>("path", field(3), false)?
+            .visit_field::<ForwardsUOffset<TextEntries>>("args", field(4), false)?
+            .visit_field::<ForwardsUOffset<TextEntries>>("headers", field(5), false)?
+            .visit_field::<u32>("conf_token", field(6), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.HTTPReqCall.Resp { id: uint32; action: Action; }`
+#[cfg(test)]
+impl<'a> HttpReqCallResp<'a> {
+    pub(crate) fn id(&self) -> u32 {
+        self.scalar(0, 0)
+    }
+
+    pub(crate) fn action_type(&self) -> u8 {
+        self.scalar(1, 0)
+    }
+
+    pub(crate) fn action_as_stop(&self) -> Option<Stop<'a>> {
+        (self.action_type() == ACTION_STOP)
+            .then(|| self.offset::<Stop>(2))
+            .flatten()
+    }
+
+    pub(crate) fn action_as_rewrite(&self) -> Option<Rewrite<'a>> {
+        (self.action_type() == ACTION_REWRITE)
+            .then(|| self.offset::<Rewrite>(2))
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+impl Verifiable for HttpReqCallResp<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<u32>("id", field(0), false)?
+            .visit_union::<u8, _>(
+                "action_type",
+                field(1),
+                "action",
+                field(2),
+                false,
+                |action_type, v, pos| match action_type {
+                    ACTION_STOP => v.verify_union_variant::<ForwardsUOffset<Stop>>("Stop", pos),
+                    ACTION_REWRITE => {
+                        v.verify_union_variant::<ForwardsUOffset<Rewrite>>("Rewrite", pos)
+                    }
+                    _ => Ok(()),
+                },
+            )?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.HTTPRespCall.Req { id: uint32; status: uint16; headers: [TextEntry]; conf_token: uint32; }`
+impl<'a> HttpRespCallReq<'a> {
+    pub(crate) fn id(&self) -> u32 {
+        self.scalar(0, 0)
+    }
+
+    pub(crate) fn status(&self) -> u16 {
+        self.scalar(1, 0)
+    }
+
+    pub(crate) fn headers(&self) -> Option<TextEntries<'a>> {
+        self.offset::<TextEntries>(2)
+    }
+
+    pub(crate) fn conf_token(&self) -> u32 {
+        self.scalar(3, 0)
+    }
+}
+
+impl Verifiable for HttpRespCallReq<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<u32>("id", field(0), false)?
+            .visit_field::<u16>("status", field(1), false)?
+            .visit_field::<ForwardsUOffset<TextEntries>>("headers", field(2), false)?
+            .visit_field::<u32>("conf_token", field(3), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.HTTPRespCall.Resp { id: uint32; status: uint16; headers: [TextEntry]; body: [ubyte]; }`
+#[cfg(test)]
+impl<'a> HttpRespCallResp<'a> {
+    pub(crate) fn id(&self) -> u32 {
+        self.scalar(0, 0)
+    }
+
+    pub(crate) fn status(&self) -> u16 {
+        self.scalar(1, 0)
+    }
+
+    pub(crate) fn headers(&self) -> Option<TextEntries<'a>> {
+        self.offset::<TextEntries>(2)
+    }
+
+    pub(crate) fn body(&self) -> Option<&'a [u8]> {
+        self.offset::<Vector<u8>>(3).map(|body| body.bytes())
+    }
+}
+
+#[cfg(test)]
+impl Verifiable for HttpRespCallResp<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<u32>("id", field(0), false)?
+            .visit_field::<u16>("status", field(1), false)?
+            .visit_field::<ForwardsUOffset<TextEntries>>("headers", field(2), false)?
+            .visit_field::<ForwardsUOffset<Vector<u8>>>("body", field(3), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.HTTPReqCall.Stop { status: uint16; headers: [TextEntry]; body: [ubyte]; }`
+#[cfg(test)]
+impl<'a> Stop<'a> {
+    pub(crate) fn status(&self) -> u16 {
+        self.scalar(0, 0)
+    }
+
+    pub(crate) fn headers(&self) -> Option<TextEntries<'a>> {
+        self.offset::<TextEntries>(1)
+    }
+
+    pub(crate) fn body(&self) -> Option<&'a [u8]> {
+        self.offset::<Vector<u8>>(2).map(|body| body.bytes())
+    }
+}
+
+#[cfg(test)]
+impl Verifiable for Stop<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<u16>("status", field(0), false)?
+            .visit_field::<ForwardsUOffset<TextEntries>>("headers", field(1), false)?
+            .visit_field::<ForwardsUOffset<Vector<u8>>>("body", field(2), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.HTTPReqCall.Rewrite { path: string; headers: [TextEntry]; args: [TextEntry]; resp_headers: [TextEntry]; }`
+#[cfg(test)]
+impl<'a> Rewrite<'a> {
+    pub(crate) fn path(&self) -> Option<&'a str> {
+        self.offset::<&str>(0)
+    }
+
+    pub(crate) fn headers(&self) -> Option<TextEntries<'a>> {
+        self.offset::<TextEntries>(1)
+    }
+
+    pub(crate) fn args(&self) -> Option<TextEntries<'a>> {
+        self.offset::<TextEntries>(2)
+    }
+
+    pub(crate) fn resp_headers(&self) -> Option<TextEntries<'a>> {
+        self.offset::<TextEntries>(3)
+    }
+}
+
+#[cfg(test)]
+impl Verifiable for Rewrite<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<ForwardsUOffset<&str>>("path", field(0), false)?
+            .visit_field::<ForwardsUOffset<TextEntries>>("headers", field(1), false)?
+            .visit_field::<ForwardsUOffset<TextEntries>>("args", field(2), false)?
+            .visit_field::<ForwardsUOffset<TextEntries>>("resp_headers", field(3), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.ExtraInfo.Req { info: Info; }`
+#[cfg(test)]
+impl<'a> ExtraInfoReq<'a> {
+    pub(crate) fn info_type(&self) -> u8 {
+        self.scalar(0, 0)
+    }
+
+    pub(crate) fn info_as_var(&self) -> Option<ExtraInfoVar<'a>> {
+        (self.info_type() == INFO_VAR)
+            .then(|| self.offset::<ExtraInfoVar>(1))
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+impl Verifiable for ExtraInfoReq<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_union::<u8, _>(
+                "info_type",
+                field(0),
+                "info",
+                field(1),
+                false,
+                |info_type, v, pos| match info_type {
+                    INFO_VAR => v.verify_union_variant::<ForwardsUOffset<ExtraInfoVar>>("Var", pos),
+                    _ => Ok(()),
+                },
+            )?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.ExtraInfo.Var { name: string; }`
+#[cfg(test)]
+impl<'a> ExtraInfoVar<'a> {
+    pub(crate) fn name(&self) -> &'a str {
+        self.offset::<&str>(0).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+impl Verifiable for ExtraInfoVar<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<ForwardsUOffset<&str>>("name", field(0), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// `A6.ExtraInfo.Resp { result: [ubyte]; }`
+impl<'a> ExtraInfoResp<'a> {
+    pub(crate) fn result(&self) -> &'a [u8] {
+        self.offset::<Vector<u8>>(0)
+            .map(|result| result.bytes())
+            .unwrap_or_default()
+    }
+}
+
+impl Verifiable for ExtraInfoResp<'_> {
+    fn run_verifier(v: &mut Verifier, pos: usize) -> Result<(), InvalidFlatbuffer> {
+        v.visit_table(pos)?
+            .visit_field::<ForwardsUOffset<Vector<u8>>>("result", field(0), false)?
+            .finish();
+        Ok(())
+    }
+}
+
+/// Collects text entries; a missing value means "delete" in responses.
+pub(crate) fn text_entries(entries: Option<TextEntries<'_>>) -> Vec<(String, Option<String>)> {
+    entries
+        .into_iter()
+        .flatten()
+        .map(|entry| (entry.name().to_owned(), entry.value().map(str::to_owned)))
+        .collect()
+}
+
+pub(crate) fn build_text_entries<'b>(
+    fbb: &mut FlatBufferBuilder<'b>,
+    entries: &[(String, Option<String>)],
+) -> WIPOffset<Vector<'b, ForwardsUOffset<TextEntry<'b>>>> {
+    let offsets: Vec<_> = entries
+        .iter()
+        .map(|(name, value)| {
+            let name = fbb.create_string(name);
+            let value = value.as_deref().map(|value| fbb.create_string(value));
+            let start = fbb.start_table();
+            fbb.push_slot_always(field(0), name);
+            if let Some(value) = value {
+                fbb.push_slot_always(field(1), value);
+            }
+            WIPOffset::<TextEntry>::new(fbb.end_table(start).value())
+        })
+        .collect();
+    fbb.create_vector(&offsets)
+}
+
+fn finish(
+    mut fbb: FlatBufferBuilder<'_>,
+    start: WIPOffset<flatbuffers::TableUnfinishedWIPOffset>,
+) -> Vec<u8> {
+    let root = fbb.end_table(start);
+    fbb.finish_minimal(root);
+    fbb.finished_data().to_vec()
+}
+
+pub(crate) fn err_resp(code: ErrorCode) -> Vec<u8> {
+    let mut fbb = FlatBufferBuilder::new();
+    let start = fbb.start_table();
+    fbb.push_slot::<u32>(field(0), code as u32, 0);
+    finish(fbb, start)
+}
+
+pub(crate) fn prepare_conf_resp(conf_token: u32) -> Vec<u8> {
+    let mut fbb = FlatBufferBuilder::new();
+    let start = fbb.start_table();
+    fbb.push_slot::<u32>(field(0), conf_token, 0);
+    finish(fbb, start)
+}
+
+/// An `HTTPReqCall.Resp` without action, i.e. the request continues unchanged.
+pub(crate) fn http_req_call_resp(id: u32) -> Vec<u8> {
+    let mut fbb = FlatBufferBuilder::new();
+    let start = fbb.start_table();
+    fbb.push_slot::<u32>(field(0), id, 0);
+    fbb.push_slot::<u8>(field(1), ACTION_NONE, ACTION_NONE);
+    finish(fbb, start)
+}
+
+/// An `HTTPReqCall.Resp` answering the client directly instead of proxying the request.
+pub(crate) fn http_req_call_stop(
+    id: u32,
+    status: u16,
+    headers: &[(String, Option<String>)],
+    body: &[u8],
+) -> Vec<u8> {
+    let mut fbb = FlatBufferBuilder::new();
+    let headers = (!headers.is_empty()).then(|| build_text_entries(&mut fbb, headers));
+    let body = (!body.is_empty()).then(|| fbb.create_vector(body));
+    let start = fbb.start_table();
+    fbb.push_slot::<u16>(field(0), status, 0);
+    if let Some(headers) = headers {
+        fbb.push_slot_always(field(1), headers);
+    }
+    if let Some(body) = body {
+        fbb.push_slot_always(field(2), body);
+    }
+    let action = fbb.end_table(start);
+    action_resp(fbb, id, ACTION_STOP, action)
+}
+
+/// An `HTTPReqCall.Resp` proxying a rewritten request.
+pub(crate) fn http_req_call_rewrite(
+    id: u32,
+    path: Option<&str>,
+    headers: &[(String, Option<String>)],
+    args: &[(String, Option<String>)],
+    resp_headers: &[(String, Option<String>)],
+) -> Vec<u8> {
+    let mut fbb = FlatBufferBuilder::new();
+    let path = path.map(|path| fbb.create_string(path));
+    let headers = (!headers.is_empty()).then(|| build_text_entries(&mut fbb, headers));
+    let args = (!args.is_empty()).then(|| build_text_entries(&mut fbb, args));
+    let resp_headers =
+        (!resp_headers.is_empty()).then(|| build_text_entries(&mut fbb, resp_headers));
+    let start = fbb.start_table();
+    if let Some(path) = path {
+        fbb.push_slot_always(field(0), path);
+    }
+    if let Some(headers) = headers {
+        fbb.push_slot_always(field(1), headers);
+    }
+    if let Some(args) = args {
+        fbb.push_slot_always(field(2), args);
+    }
+    if let Some(resp_headers) = resp_headers {
+        fbb.push_slot_always(field(3), resp_headers);
+    }
+    let action = fbb.end_table(start);
+    action_resp(fbb, id, ACTION_REWRITE, action)
+}
+
+fn action_resp(
+    mut fbb: FlatBufferBuilder<'_>,
+    id: u32,
+    action_type: u8,
+    action: WIPOffset<flatbuffers::TableFinishedWIPOffset>,
+) -> Vec<u8> {
+    let start = fbb.start_table();
+    fbb.push_slot::<u32>(field(0), id, 0);
+    fbb.push_slot::<u8>(field(1), action_type, ACTION_NONE);
+    fbb.push_slot_always(field(2), action);
+    finish(fbb, start)
+}
+
+pub(crate) fn extra_info_req(info: Info<'_>) -> Vec<u8> {
+    let mut fbb = FlatBufferBuilder::new();
+    let (info_type, info) = match info {
+        Info::Var(name) => {
+            let name = fbb.create_string(name);
+            let start = fbb.start_table();
+            fbb.push_slot_always(field(0), name);
+            (INFO_VAR, fbb.end_table(start))
+        }
+        Info::ReqBody => {
+            let start = fbb.start_table();
+            (INFO_REQ_BODY, fbb.end_table(start))
+        }
+        Info::RespBody => {
+            let start = fbb.start_table();
+            (INFO_RESP_BODY, fbb.end_table(start))
+        }
+    };
+    let start = fbb.start_table();
+    fbb.push_slot::<u8>(field(0), info_type, 0);
+    fbb.push_slot_always(field(1), info);
+    finish(fbb, start)
+}
+
+/// An `HTTPRespCall.Resp`; status `0`, no headers and no body leave the response unchanged.
+pub(crate) fn http_resp_call_resp(
+    id: u32,
+    status: u16,
+    headers: &[(String, Option<String>)],
+    body: Option<&[u8]>,
+) -> Vec<u8> {
+    let mut fbb = FlatBufferBuilder::new();
+    let headers = (!headers.is_empty()).then(|| build_text_entries(&mut fbb, headers));
+    let body = body.map(|body| fbb.create_vector(body));
+    let start = fbb.start_table();
+    fbb.push_slot::<u32>(field(0), id, 0);
+    fbb.push_slot::<u16>(field(1), status, 0);
+    if let Some(headers) = headers {
+        fbb.push_slot_always(field(2), headers);
+    }
+    if let Some(body) = body {
+        fbb.push_slot_always(field(3), body);
+    }
+    finish(fbb, start)
+}
+
+/// Builders for the messages APISIX sends, used to fake APISIX in tests.
+#[cfg(test)]
+pub(crate) mod apisix {
+    use super::*;
+
+    pub(crate) fn prepare_conf_req(key: &str, conf: &[(&str, &str)]) -> Vec<u8> {
+        let mut fbb = FlatBufferBuilder::new();
+        let conf: Vec<_> = conf
+            .iter()
+            .map(|(name, value)| (name.to_string(), Some(value.to_string())))
+            .collect();
+        let conf = build_text_entries(&mut fbb, &conf);
+        let key = fbb.create_string(key);
+        let start = fbb.start_table();
+        fbb.push_slot_always(field(0), conf);
+        fbb.push_slot_always(field(1), key);
+        finish(fbb, start)
+    }
+
+    pub(crate) struct ReqCall<'r> {
+        pub(crate) id: u32,
+        pub(crate) conf_token: u32,
+        pub(crate) src_ip: &'r [u8],
+        pub(crate) method: u8,
+        pub(crate) path: &'r str,
+        pub(crate) args: &'r [(&'r str, &'r str)],
+        pub(crate) headers: &'r [(&'r str, &'r str)],
+    }
+
+    fn entries(entries: &[(&str, &str)]) -> Vec<(String, Option<String>)> {
+        entries
+            .iter()
+            .map(|(name, value)| (name.to_string(), Some(value.to_string())))
+            .collect()
+    }
+
+    pub(crate) fn http_req_call_req(call: &ReqCall<'_>) -> Vec<u8> {
+        let mut fbb = FlatBufferBuilder::new();
+        let src_ip = fbb.create_vector(call.src_ip);
+        let path = fbb.create_string(call.path);
+        let args = build_text_entries(&mut fbb, &entries(call.args));
+        let headers = build_text_entries(&mut fbb, &entries(call.headers));
+        let start = fbb.start_table();
+        fbb.push_slot::<u32>(field(0), call.id, 0);
+        fbb.push_slot_always(field(1), src_ip);
+        fbb.push_slot::<u8>(field(2), call.method, 0);
+        fbb.push_slot_always(field(3), path);
+        fbb.push_slot_always(field(4), args);
+        fbb.push_slot_always(field(5), headers);
+        fbb.push_slot::<u32>(field(6), call.conf_token, 0);
+        finish(fbb, start)
+    }
+
+    pub(crate) fn extra_info_resp(result: &[u8]) -> Vec<u8> {
+        let mut fbb = FlatBufferBuilder::new();
+        let result = fbb.create_vector(result);
+        let start = fbb.start_table();
+        fbb.push_slot_always(field(0), result);
+        finish(fbb, start)
+    }
+
+    pub(crate) fn http_resp_call_req(
+        id: u32,
+        conf_token: u32,
+        status: u16,
+        headers: &[(&str, &str)],
+    ) -> Vec<u8> {
+        let mut fbb = FlatBufferBuilder::new();
+        let headers = build_text_entries(&mut fbb, &entries(headers));
+        let start = fbb.start_table();
+        fbb.push_slot::<u32>(field(0), id, 0);
+        fbb.push_slot::<u16>(field(1), status, 0);
+        fbb.push_slot_always(field(2), headers);
+        fbb.push_slot::<u32>(field(3), conf_token, 0);
+        finish(fbb, start)
+    }
+}
@@ -0,0 +1,687 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    env, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use tokio::net::{UnixListener, UnixStream};
+
+use super::{
+    proto::{self, ErrorCode, HttpReqCallReq, HttpRespCallReq, PrepareConfReq},
+    Conn, Request, Response,
+};
+
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+const LISTEN_ADDRESS_ENV: &str = "APISIX_LISTEN_ADDRESS";
+
+const CONF_EXPIRE_TIME_ENV: &str = "APISIX_CONF_EXPIRE_TIME";
+
+/// How long a prepared conf stays valid unless `APISIX_CONF_EXPIRE_TIME` says otherwise.
+const DEFAULT_CONF_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Error, Debug)]
+pub enum RunnerError {
+    #[error("{LISTEN_ADDRESS_ENV} is not set")]
+    MissingListenAddress,
+
+    #[error("unsupported listen address {0}, expected unix:/path")]
+    InvalidListenAddress(String),
+
+    #[error("runner io error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("plugin {0} is not registered with this runner")]
+    UnknownPlugin(String),
+
+    #[error("invalid conf for plugin {plugin}: {source}")]
+    InvalidConf {
+        plugin: &'static str,
+        source: serde_json::Error,
+    },
+
+    #[error("plugin {plugin} failed: {source}")]
+    Plugin {
+        plugin: &'static str,
+        source: BoxError,
+    },
+}
+
+type ErrorHandler = Arc<dyn Fn(RunnerError) + Send + Sync>;
+
+/// An external plugin, referenced by [`Plugin::NAME`] in `ext-plugin-*.conf[].name`.
+///
+/// The matching `conf[].value` is parsed as JSON into [`Plugin::Conf`] once per prepared conf.
+#[async_trait]
+pub trait Plugin: Send + Sync + 'static {
+    const NAME: &'static str;
+
+    type Conf: DeserializeOwned + Send + Sync + 'static;
+
+    /// Runs for `ext-plugin-pre-req` and `ext-plugin-post-req`.
+    async fn filter(&self, _conf: &Self::Conf, _request: &mut Request) -> Result<(), BoxError> {
+        Ok(())
+    }
+
+    /// Runs for `ext-plugin-post-resp`.
+    async fn response_filter(
+        &self,
+        _conf: &Self::Conf,
+        _response: &mut Response,
+    ) -> Result<(), BoxError> {
+        Ok(())
+    }
+}
+
+type Conf = Arc<dyn Any + Send + Sync>;
+
+#[async_trait]
+trait ErasedPlugin: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn parse_conf(&self, value: &str) -> Result<Conf, serde_json::Error>;
+
+    async fn filter(&self, conf: &Conf, request: &mut Request) -> Result<(), BoxError>;
+
+    async fn response_filter(&self, conf: &Conf, response: &mut Response) -> Result<(), BoxError>;
+}
+
+#[async_trait]
+impl<P> ErasedPlugin for P
+where
+    P: Plugin,
+{
+    fn name(&self) -> &'static str {
+        P::NAME
+    }
+
+    fn parse_conf(&self, value: &str) -> Result<Conf, serde_json::Error> {
+        // An empty value is treated like `null` so that `()` and `Option` confs work.
+        let value = if value.trim().is_empty() {
+            "null"
+        } else {
+            value
+        };
+        Ok(Arc::new(serde_json::from_str::<P::Conf>(value)?))
+    }
+
+    async fn filter(&self, conf: &Conf, request: &mut Request) -> Result<(), BoxError> {
+        Plugin::filter(self, downcast::<P>(conf), request).await
+    }
+
+    async fn response_filter(&self, conf: &Conf, response: &mut Response) -> Result<(), BoxError> {
+        Plugin::response_filter(self, downcast::<P>(conf), response).await
+    }
+}
+
+fn downcast<P>(conf: &Conf) -> &P::Conf
+where
+    P: Plugin,
+{
+    conf.downcast_ref()
+        .expect("conf was parsed by the same plugin")
+}
+
+/// The plugins of one `PrepareConf` call, in configuration order.
+type PreparedConf = Vec<(Arc<dyn ErasedPlugin>, Conf)>;
+
+struct CachedConf {
+    conf: Arc<PreparedConf>,
+    expires_at: Instant,
+}
+
+#[derive(Default)]
+struct ConfCache {
+    next_token: u32,
+    by_token: HashMap<u32, CachedConf>,
+    by_key: HashMap<String, u32>,
+}
+
+impl ConfCache {
+    fn get(&self, token: u32) -> Option<Arc<PreparedConf>> {
+        self.by_token
+            .get(&token)
+            .filter(|cached| cached.expires_at > Instant::now())
+            .map(|cached| cached.conf.clone())
+    }
+
+    fn token_for_key(&self, key: &str) -> Option<u32> {
+        let token = *self.by_key.get(key)?;
+        self.get(token).map(|_| token)
+    }
+
+    fn insert(&mut self, key: String, conf: PreparedConf, ttl: Duration) -> u32 {
+        let now = Instant::now();
+        self.by_token.retain(|_, cached| cached.expires_at > now);
+        let by_token = &self.by_token;
+        self.by_key.retain(|_, token| by_token.contains_key(token));
+
+        // Token 0 is never handed out.
+        self.next_token = self.next_token.wrapping_add(1).max(1);
+        let token = self.next_token;
+        if !key.is_empty() {
+            self.by_key.insert(key, token);
+        }
+        self.by_token.insert(
+            token,
+            CachedConf {
+                conf: Arc::new(conf),
+                expires_at: now + ttl,
+            },
+        );
+        token
+    }
+}
+
+struct Inner {
+    plugins: HashMap<&'static str, Arc<dyn ErasedPlugin>>,
+    confs: Mutex<ConfCache>,
+    conf_ttl: Duration,
+    on_error: ErrorHandler,
+}
+
+/// Serves registered [`Plugin`]s to APISIX.
+///
+/// ```no_run
+/// # async fn run() -> Result<(), apisix_rs::ext_plugin::RunnerError> {
+/// # struct Auth;
+/// # #[apisix_rs::ext_plugin::async_trait]
+/// # impl apisix_rs::ext_plugin::Plugin for Auth { const NAME: &'static str = "auth"; type Conf = (); }
+/// apisix_rs::ext_plugin::Runner::new()
+///     .plugin(Auth)
+///     .serve_from_env()
+///     .await
+/// # }
+/// ```
+pub struct Runner {
+    plugins: HashMap<&'static str, Arc<dyn ErasedPlugin>>,
+    conf_ttl: Duration,
+    on_error: ErrorHandler,
+}
+
+impl Default for Runner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Runner {
+    pub fn new() -> Self {
+        Runner {
+            plugins: HashMap::new(),
+            conf_ttl: DEFAULT_CONF_TTL,
+            // APISIX copies the runner's stderr into its error log.
+            on_error: Arc::new(|err| eprintln!("ext-plugin runner: {err}")),
+        }
+    }
+
+    pub fn plugin<P>(mut self, plugin: P) -> Self
+    where
+        P: Plugin,
+    {
+        self.plugins.insert(P::NAME, Arc::new(plugin));
+        self
+    }
+
+    /// How long prepared confs are cached; APISIX re-prepares a conf once it expires.
+    pub fn conf_ttl(mut self, ttl: Duration) -> Self {
+        self.conf_ttl = ttl;
+        self
+    }
+
+    /// Receives the errors that APISIX only sees as an error code: failed plugins, invalid or
+    /// unknown plugin confs and broken connections. They are printed to stderr by default.
+    pub fn on_error<F>(mut self, on_error: F) -> Self
+    where
+        F: Fn(RunnerError) + Send + Sync + 'static,
+    {
+        self.on_error = Arc::new(on_error);
+        self
+    }
+
+    /// Listens on the socket given by `APISIX_LISTEN_ADDRESS`, e.g. `unix:/tmp/runner.sock`.
+    pub async fn serve_from_env(mut self) -> Result<(), RunnerError> {
+        let address =
+            env::var(LISTEN_ADDRESS_ENV).map_err(|_| RunnerError::MissingListenAddress)?;
+        let path = address
+            .strip_prefix("unix:")
+            .ok_or_else(|| RunnerError::InvalidListenAddress(address.clone()))?;
+
+        if let Some(ttl) = env::var(CONF_EXPIRE_TIME_ENV)
+            .ok()
+            .and_then(|ttl| ttl.parse().ok())
+        {
+            self.conf_ttl = Duration::from_secs(ttl);
+        }
+
+        self.serve(path).await
+    }
+
+    /// Listens on the Unix socket at `path`, replacing a stale socket file.
+    pub async fn serve(self, path: impl AsRef<Path>) -> Result<(), RunnerError> {
+        let path: PathBuf = path.as_ref().into();
+        match std::fs::remove_file(&path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+            _ => {}
+        }
+        self.serve_listener(UnixListener::bind(path)?).await
+    }
+
+    pub async fn serve_listener(self, listener: UnixListener) -> Result<(), RunnerError> {
+        let inner = Arc::new(Inner {
+            plugins: self.plugins,
+            confs: Mutex::default(),
+            conf_ttl: self.conf_ttl,
+            on_error: self.on_error,
+        });
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let inner = inner.clone();
+            tokio::spawn(async move {
+                // A broken connection only affects the calls on it; APISIX reconnects.
+                if let Err(err) = inner.handle(stream).await {
+                    (inner.on_error)(err.into());
+                }
+            });
+        }
+    }
+}
+
+impl Inner {
+    async fn handle(&self, stream: UnixStream) -> io::Result<()> {
+        let conn = Conn::new(stream);
+        loop {
+            let (ty, body) = match conn.read().await {
+                Ok(frame) => frame,
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+                Err(err) => return Err(err),
+            };
+
+            let (ty, body) = match self.dispatch(&conn, ty, &body).await {
+                Ok(reply) => reply,
+                Err(code) => (proto::RPC_ERROR, proto::err_resp(code)),
+            };
+            conn.write(ty, &body).await?;
+        }
+    }
+
+    async fn dispatch(&self, conn: &Conn, ty: u8, body: &[u8]) -> Result<(u8, Vec<u8>), ErrorCode> {
+        match ty {
+            proto::RPC_PREPARE_CONF => {
+                let req =
+                    flatbuffers::root::<PrepareConfReq>(body).map_err(|_| ErrorCode::BadRequest)?;
+                let token = self.prepare_conf(&req)?;
+                Ok((ty, proto::prepare_conf_resp(token)))
+            }
+            proto::RPC_HTTP_REQ_CALL => {
+                let (conf, mut request) = {
+                    let req = flatbuffers::root::<HttpReqCallReq>(body)
+                        .map_err(|_| ErrorCode::BadRequest)?;
+                    let request = Request::from_call(&req, Some(conn.clone()));
+                    (self.conf(req.conf_token())?, request)
+                };
+                for (plugin, plugin_conf) in conf.iter() {
+                    plugin
+                        .filter(plugin_conf, &mut request)
+                        .await
+                        .map_err(|err| self.plugin_failed(plugin.as_ref(), err))?;
+                    if request.stopped().is_some() {
+                        break;
+                    }
+                }
+                Ok((ty, request.encode()))
+            }
+            proto::RPC_HTTP_RESP_CALL => {
+                let (conf, mut response) = {
+                    let req = flatbuffers::root::<HttpRespCallReq>(body)
+                        .map_err(|_| ErrorCode::BadRequest)?;
+                    let response = Response::from_call(&req, Some(conn.clone()));
+                    (self.conf(req.conf_token())?, response)
+                };
+                for (plugin, plugin_conf) in conf.iter() {
+                    plugin
+                        .response_filter(plugin_conf, &mut response)
+                        .await
+                        .map_err(|err| self.plugin_failed(plugin.as_ref(), err))?;
+                }
+                Ok((ty, response.encode()))
+            }
+            _ => Err(ErrorCode::BadRequest),
+        }
+    }
+
+    fn prepare_conf(&self, req: &PrepareConfReq<'_>) -> Result<u32, ErrorCode> {
+        let key = req.key();
+        if let Some(token) = self.confs().token_for_key(key) {
+            return Ok(token);
+        }
+
+        let mut prepared = PreparedConf::new();
+        for entry in req.conf().into_iter().flatten() {
+            // Unknown plugins are skipped rather than failing every request of the route.
+            let Some(plugin) = self.plugins.get(entry.name()) else {
+                (self.on_error)(RunnerError::UnknownPlugin(entry.name().to_owned()));
+                continue;
+            };
+            let conf = plugin
+                .parse_conf(entry.value().unwrap_or_default())
+                .map_err(|source| {
+                    (self.on_error)(RunnerError::InvalidConf {
+                        plugin: plugin.name(),
+                        source,
+                    });
+                    ErrorCode::BadRequest
+                })?;
+            prepared.push((plugin.clone(), conf));
+        }
+
+        let token = self.confs().insert(key.to_owned(), prepared, self.conf_ttl);
+        Ok(token)
+    }
+
+    fn conf(&self, token: u32) -> Result<Arc<PreparedConf>, ErrorCode> {
+        self.confs().get(token).ok_or(ErrorCode::ConfTokenNotFound)
+    }
+
+    fn confs(&self) -> MutexGuard<'_, ConfCache> {
+        // The cache stays consistent even if a holder panicked.
+        self.confs.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn plugin_failed(&self, plugin: &dyn ErasedPlugin, source: BoxError) -> ErrorCode {
+        (self.on_error)(RunnerError::Plugin {
+            plugin: plugin.name(),
+            source,
+        });
+        ErrorCode::ServiceUnavailable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ext_plugin::{
+        proto::{
+            apisix::{self, ReqCall},
+            ErrResp, ExtraInfoReq, HttpReqCallResp, HttpRespCallResp, PrepareConfResp,
+        },
+        read_frame, write_frame, Method, Stop,
+    };
+    use serde::Deserialize;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Deserialize)]
+    struct TenantConf {
+        tenant: String,
+    }
+
+    #[derive(Default)]
+    struct Tenant {
+        seen: Mutex<Vec<String>>,
+        responses: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Plugin for Arc<Tenant> {
+        const NAME: &'static str = "tenant";
+
+        type Conf = TenantConf;
+
+        async fn filter(&self, conf: &TenantConf, request: &mut Request) -> Result<(), BoxError> {
+            if request.header("x-fail").is_some() {
+                return Err("rejected".into());
+            }
+            if request.header("x-stop").is_some() {
+                let addr = request.var("remote_addr").await?;
+                let body = request.read_body().await?;
+                request.stop(Stop::new(403).body([addr, body].join(&b' ')));
+                return Ok(());
+            }
+            self.seen.lock().unwrap().push(format!(
+                "{} {} {} {}",
+                conf.tenant,
+                request.method().map(Method::as_str).unwrap_or_default(),
+                request.path(),
+                request.header("X-Request-Id").unwrap_or_default(),
+            ));
+            Ok(())
+        }
+
+        async fn response_filter(
+            &self,
+            _conf: &TenantConf,
+            response: &mut Response,
+        ) -> Result<(), BoxError> {
+            assert_eq!(response.status(), 201);
+            self.responses.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn socket_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("apisix-rs-{}-{name}.sock", std::process::id()))
+    }
+
+    async fn start(name: &str, tenant: Arc<Tenant>) -> UnixStream {
+        start_runner(name, Runner::new().plugin(tenant)).await
+    }
+
+    async fn start_runner(name: &str, runner: Runner) -> UnixStream {
+        let path = socket_path(name);
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(runner.serve_listener(listener));
+        UnixStream::connect(&path).await.unwrap()
+    }
+
+    async fn call(stream: &mut UnixStream, ty: u8, body: &[u8]) -> (u8, Vec<u8>) {
+        write_frame(stream, ty, body).await.unwrap();
+        read_frame(stream).await.unwrap()
+    }
+
+    async fn prepare(stream: &mut UnixStream, key: &str, conf: &[(&str, &str)]) -> u32 {
+        let (ty, body) = call(
+            stream,
+            proto::RPC_PREPARE_CONF,
+            &apisix::prepare_conf_req(key, conf),
+        )
+        .await;
+        assert_eq!(ty, proto::RPC_PREPARE_CONF);
+        flatbuffers::root::<PrepareConfResp>(&body)
+            .unwrap()
+            .conf_token()
+    }
+
+    fn req_call(id: u32, conf_token: u32, headers: &[(&str, &str)]) -> Vec<u8> {
+        apisix::http_req_call_req(&ReqCall {
+            id,
+            conf_token,
+            src_ip: &[127, 0, 0, 1],
+            method: 2,
+            path: "/orders",
+            args: &[("page", "2")],
+            headers,
+        })
+    }
+
+    fn error_code(ty: u8, body: &[u8]) -> u32 {
+        assert_eq!(ty, proto::RPC_ERROR);
+        flatbuffers::root::<ErrResp>(body).unwrap().code()
+    }
+
+    #[tokio::test]
+    async fn test_prepare_conf() {
+        let mut stream = start("prepare", Arc::default()).await;
+
+        let conf = [("other", "{}"), ("tenant", r#"{"tenant":"acme"}"#)];
+        let token = prepare(&mut stream, "route#1", &conf).await;
+        assert_ne!(token, 0);
+        assert_eq!(prepare(&mut stream, "route#1", &conf).await, token);
+        assert_ne!(prepare(&mut stream, "route#2", &conf).await, token);
+
+        let (ty, body) = call(
+            &mut stream,
+            proto::RPC_PREPARE_CONF,
+            &apisix::prepare_conf_req("route#3", &[("tenant", "not json")]),
+        )
+        .await;
+        assert_eq!(error_code(ty, &body), ErrorCode::BadRequest as u32);
+    }
+
+    #[tokio::test]
+    async fn test_http_calls() {
+        let tenant = Arc::new(Tenant::default());
+        let mut stream = start("calls", tenant.clone()).await;
+        let token = prepare(
+            &mut stream,
+            "route#1",
+            &[("tenant", r#"{"tenant":"acme"}"#)],
+        )
+        .await;
+
+        let (ty, body) = call(
+            &mut stream,
+            proto::RPC_HTTP_REQ_CALL,
+            &req_call(7, token, &[("x-request-id", "abc")]),
+        )
+        .await;
+        assert_eq!(ty, proto::RPC_HTTP_REQ_CALL);
+        let resp = flatbuffers::root::<HttpReqCallResp>(&body).unwrap();
+        assert_eq!(resp.id(), 7);
+        assert_eq!(resp.action_type(), 0);
+        assert_eq!(*tenant.seen.lock().unwrap(), ["acme POST /orders abc"]);
+
+        let (ty, body) = call(
+            &mut stream,
+            proto::RPC_HTTP_RESP_CALL,
+            &apisix::http_resp_call_req(8, token, 201, &[("content-type", "text/plain")]),
+        )
+        .await;
+        assert_eq!(ty, proto::RPC_HTTP_RESP_CALL);
+        let resp = flatbuffers::root::<HttpRespCallResp>(&body).unwrap();
+        assert_eq!(resp.id(), 8);
+        assert_eq!(resp.status(), 0);
+        assert!(resp.headers().is_none());
+        assert!(resp.body().is_none());
+        assert_eq!(tenant.responses.load(Ordering::SeqCst), 1);
+
+        let (ty, body) = call(
+            &mut stream,
+            proto::RPC_HTTP_REQ_CALL,
+            &req_call(9, token, &[("x-fail", "1")]),
+        )
+        .await;
+        assert_eq!(error_code(ty, &body), ErrorCode::ServiceUnavailable as u32);
+    }
+
+    #[tokio::test]
+    async fn test_extra_info() {
+        let tenant = Arc::new(Tenant::default());
+        let mut stream = start("extra-info", tenant.clone()).await;
+        let token = prepare(
+            &mut stream,
+            "route#1",
+            &[("tenant", r#"{"tenant":"acme"}"#)],
+        )
+        .await;
+
+        write_frame(
+            &mut stream,
+            proto::RPC_HTTP_REQ_CALL,
+            &req_call(3, token, &[("x-stop", "1")]),
+        )
+        .await
+        .unwrap();
+
+        let (ty, body) = read_frame(&mut stream).await.unwrap();
+        assert_eq!(ty, proto::RPC_EXTRA_INFO);
+        let req = flatbuffers::root::<ExtraInfoReq>(&body).unwrap();
+        assert_eq!(req.info_as_var().unwrap().name(), "remote_addr");
+        let (ty, body) = call(
+            &mut stream,
+            proto::RPC_EXTRA_INFO,
+            &apisix::extra_info_resp(b"10.0.0.1"),
+        )
+        .await;
+        assert_eq!(ty, proto::RPC_EXTRA_INFO);
+        let req = flatbuffers::root::<ExtraInfoReq>(&body).unwrap();
+        assert_eq!(req.info_type(), proto::INFO_REQ_BODY);
+
+        let (ty, body) = call(
+            &mut stream,
+            proto::RPC_EXTRA_INFO,
+            &apisix::extra_info_resp(b"{}"),
+        )
+        .await;
+        assert_eq!(ty, proto::RPC_HTTP_REQ_CALL);
+        let resp = flatbuffers::root::<HttpReqCallResp>(&body).unwrap();
+        assert_eq!(resp.id(), 3);
+        let stop = resp.action_as_stop().unwrap();
+        assert_eq!(stop.status(), 403);
+        assert_eq!(stop.body(), Some(&b"10.0.0.1 {}"[..]));
+        assert!(tenant.seen.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_on_error() {
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let runner = Runner::new().plugin(Arc::<Tenant>::default()).on_error({
+            let errors = errors.clone();
+            move |err| errors.lock().unwrap().push(err.to_string())
+        });
+        let mut stream = start_runner("on-error", runner).await;
+
+        let conf = [("other", "{}"), ("tenant", r#"{"tenant":"acme"}"#)];
+        let token = prepare(&mut stream, "route#1", &conf).await;
+        call(
+            &mut stream,
+            proto::RPC_PREPARE_CONF,
+            &apisix::prepare_conf_req("route#2", &[("tenant", "{}")]),
+        )
+        .await;
+        call(
+            &mut stream,
+            proto::RPC_HTTP_REQ_CALL,
+            &req_call(1, token, &[("x-fail", "1")]),
+        )
+        .await;
+
+        assert_eq!(
+            *errors.lock().unwrap(),
+            [
+                "plugin other is not registered with this runner",
+                "invalid conf for plugin tenant: missing field `tenant` at line 1 column 2",
+                "plugin tenant failed: rejected",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_conf_token() {
+        let mut stream = start("unknown-token", Arc::default()).await;
+
+        let (ty, body) = call(&mut stream, proto::RPC_HTTP_REQ_CALL, &req_call(1, 42, &[])).await;
+        assert_eq!(error_code(ty, &body), ErrorCode::ConfTokenNotFound as u32);
+
+        let (ty, body) = call(&mut stream, proto::RPC_HTTP_REQ_CALL, b"garbage").await;
+        assert_eq!(error_code(ty, &body), ErrorCode::BadRequest as u32);
+    }
+
+    #[test]
+    fn test_conf_expiry() {
+        let mut cache = ConfCache::default();
+        let token = cache.insert("route#1".into(), Vec::new(), Duration::ZERO);
+        assert!(cache.get(token).is_none());
+        assert!(cache.token_for_key("route#1").is_none());
+
+        let token = cache.insert("route#1".into(), Vec::new(), DEFAULT_CONF_TTL);
+        assert_eq!(cache.token_for_key("route#1"), Some(token));
+    }
+}
@@ -0,0 +1,257 @@
+use std::future::{ready, Ready};
+
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::Payload,
+    http::{
+        header::{self, ContentType, TryIntoHeaderPair},
+        Method, StatusCode,
+    },
+    FromRequest, HttpRequest, HttpResponse, HttpResponseBuilder, Responder, ResponseError,
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    encode_user_info, X_FORWARDED_FOR_HEADER, X_FORWARDED_HOST_HEADER, X_FORWARDED_METHOD_HEADER,
+    X_FORWARDED_PROTO_HEADER, X_FORWARDED_URI_HEADER, X_USER_INFO_HEADER,
+};
+
+#[derive(Error, Debug)]
+pub enum ForwardAuthError {
+    #[error("{0} header is missing")]
+    MissingHeader(&'static str),
+
+    #[error("invalid {0} header: {1}")]
+    ToStringError(&'static str, header::ToStrError),
+
+    #[error("invalid x-forwarded-method: {0}")]
+    InvalidMethod(String),
+}
+
+impl ResponseError for ForwardAuthError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::plaintext())
+            .body(self.to_string())
+    }
+
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+/// The client request APISIX's `forward-auth` plugin asks an auth service to authorize.
+///
+/// Headers listed in the plugin's `request_headers` are forwarded as they are and can be read
+/// from the [`HttpRequest`] itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForwardAuthRequest {
+    pub method: Method,
+    pub proto: Option<String>,
+    pub host: Option<String>,
+    /// The request URI including the query string.
+    pub uri: String,
+    pub forwarded_for: Option<String>,
+}
+
+impl ForwardAuthRequest {
+    pub fn path(&self) -> &str {
+        self.uri.split_once('?').map_or(&self.uri, |(path, _)| path)
+    }
+
+    pub fn query(&self) -> Option<&str> {
+        self.uri.split_once('?').map(|(_, query)| query)
+    }
+}
+
+fn forwarded_header(
+    req: &HttpRequest,
+    name: &'static str,
+) -> Result<Option<String>, ForwardAuthError> {
+    req.headers()
+        .get(name)
+        .map(|value| {
+            value
+                .to_str()
+                .map(str::to_owned)
+                .map_err(|err| ForwardAuthError::ToStringError(name, err))
+        })
+        .transpose()
+}
+
+impl FromRequest for ForwardAuthRequest {
+    type Error = ForwardAuthError;
+
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(req.try_into())
+    }
+}
+
+impl TryFrom<&HttpRequest> for ForwardAuthRequest {
+    type Error = ForwardAuthError;
+
+    fn try_from(req: &HttpRequest) -> Result<Self, Self::Error> {
+        let method = forwarded_header(req, X_FORWARDED_METHOD_HEADER)?
+            .ok_or(ForwardAuthError::MissingHeader(X_FORWARDED_METHOD_HEADER))?;
+        let uri = forwarded_header(req, X_FORWARDED_URI_HEADER)?
+            .ok_or(ForwardAuthError::MissingHeader(X_FORWARDED_URI_HEADER))?;
+
+        Ok(ForwardAuthRequest {
+            method: Method::from_bytes(method.as_bytes())
+                .map_err(|_| ForwardAuthError::InvalidMethod(method))?,
+            proto: forwarded_header(req, X_FORWARDED_PROTO_HEADER)?,
+            host: forwarded_header(req, X_FORWARDED_HOST_HEADER)?,
+            uri,
+            forwarded_for: forwarded_header(req, X_FORWARDED_FOR_HEADER)?,
+        })
+    }
+}
+
+/// The auth service's answer to a [`ForwardAuthRequest`].
+///
+/// On a 2xx reply APISIX copies the headers named in `upstream_headers` onto the proxied
+/// request, so `x-userinfo` must be listed there for [`ForwardAuthResponse::user_info`] to reach
+/// the upstream. Otherwise the reply goes back to the client with the `client_headers`.
+pub struct ForwardAuthResponse {
+    builder: HttpResponseBuilder,
+}
+
+impl ForwardAuthResponse {
+    pub fn allow() -> Self {
+        ForwardAuthResponse {
+            builder: HttpResponse::Ok(),
+        }
+    }
+
+    pub fn deny(status: StatusCode) -> Self {
+        ForwardAuthResponse {
+            builder: HttpResponse::build(status),
+        }
+    }
+
+    pub fn header(mut self, header: impl TryIntoHeaderPair) -> Self {
+        self.builder.insert_header(header);
+        self
+    }
+
+    /// Sets `x-userinfo` so that [`XUserInfo`](crate::XUserInfo) decodes `value` upstream.
+    pub fn user_info<T>(self, value: &T) -> Result<Self, serde_json::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(self.header((X_USER_INFO_HEADER, encode_user_info(value)?)))
+    }
+
+    pub fn body(mut self, body: impl MessageBody + 'static) -> HttpResponse {
+        self.builder.body(body)
+    }
+
+    pub fn finish(mut self) -> HttpResponse {
+        self.builder.finish()
+    }
+}
+
+impl Responder for ForwardAuthResponse {
+    type Body = BoxBody;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        self.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XUserInfo;
+    use actix_web::{test::TestRequest, web, App};
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Claims {
+        sub: String,
+        tenant: String,
+    }
+
+    fn forwarded() -> TestRequest {
+        TestRequest::default()
+            .insert_header((X_FORWARDED_METHOD_HEADER, "DELETE"))
+            .insert_header((X_FORWARDED_PROTO_HEADER, "https"))
+            .insert_header((X_FORWARDED_HOST_HEADER, "api.example.com"))
+            .insert_header((X_FORWARDED_URI_HEADER, "/orders/42?force=true"))
+            .insert_header((X_FORWARDED_FOR_HEADER, "10.0.0.1"))
+    }
+
+    #[actix_rt::test]
+    async fn test_forward_auth_request() {
+        let req = forwarded().to_http_request();
+        let request = ForwardAuthRequest::extract(&req).await.unwrap();
+        assert_eq!(request.method, Method::DELETE);
+        assert_eq!(request.proto.as_deref(), Some("https"));
+        assert_eq!(request.host.as_deref(), Some("api.example.com"));
+        assert_eq!(request.path(), "/orders/42");
+        assert_eq!(request.query(), Some("force=true"));
+        assert_eq!(request.forwarded_for.as_deref(), Some("10.0.0.1"));
+
+        let req = TestRequest::default()
+            .insert_header((X_FORWARDED_METHOD_HEADER, "GET"))
+            .to_http_request();
+        let err = ForwardAuthRequest::extract(&req).await.unwrap_err();
+        assert_eq!(err.to_string(), "x-forwarded-uri header is missing");
+
+        let req = forwarded()
+            .insert_header((X_FORWARDED_METHOD_HEADER, "NOT A METHOD"))
+            .to_http_request();
+        let err = ForwardAuthRequest::extract(&req).await.unwrap_err();
+        assert!(matches!(err, ForwardAuthError::InvalidMethod(_)));
+    }
+
+    #[actix_rt::test]
+    async fn test_user_info_round_trip() {
+        async fn auth(
+            forwarded: ForwardAuthRequest,
+        ) -> Result<ForwardAuthResponse, actix_web::Error> {
+            if forwarded.method == Method::DELETE {
+                return Ok(ForwardAuthResponse::deny(StatusCode::FORBIDDEN));
+            }
+            let claims = Claims {
+                sub: "user-1".into(),
+                tenant: "acme".into(),
+            };
+            Ok(ForwardAuthResponse::allow()
+                .header(("x-tenant", claims.tenant.as_str()))
+                .user_info(&claims)?)
+        }
+
+        let app =
+            actix_web::test::init_service(App::new().route("/auth", web::get().to(auth))).await;
+
+        let req = forwarded()
+            .insert_header((X_FORWARDED_METHOD_HEADER, "GET"))
+            .uri("/auth")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("x-tenant").unwrap(), "acme");
+
+        let user_info = resp.headers().get(X_USER_INFO_HEADER).unwrap().clone();
+        let upstream = TestRequest::default()
+            .insert_header((X_USER_INFO_HEADER, user_info))
+            .to_http_request();
+        let claims = XUserInfo::<Claims>::extract(&upstream).await.unwrap();
+        assert_eq!(
+            claims.into_inner(),
+            Claims {
+                sub: "user-1".into(),
+                tenant: "acme".into()
+            }
+        );
+
+        let req = forwarded().uri("/auth").to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert!(resp.headers().get(X_USER_INFO_HEADER).is_none());
+    }
+}
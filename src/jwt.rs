@@ -0,0 +1,382 @@
+use std::{fs, io, ops::Deref, path::Path, sync::RwLock};
+
+use jsonwebtoken::{
+    decode, decode_header,
+    jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet},
+    Algorithm, DecodingKey, Validation,
+};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum JwtError {
+    #[error("failed to read jwks: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("failed to parse jwks: {0}")]
+    Jwks(#[from] serde_json::Error),
+
+    #[error("no matching key found in jwks")]
+    KeyNotFound,
+
+    #[error("algorithm {0:?} is not allowed")]
+    AlgorithmNotAllowed(Algorithm),
+
+    #[error("invalid token: {0}")]
+    InvalidToken(#[from] jsonwebtoken::errors::Error),
+}
+
+/// Verifies forwarded access tokens against a locally held JWKS.
+///
+/// The key set is kept in memory and can be swapped at runtime with [`JwtVerifier::set_jwks`].
+#[derive(Debug)]
+pub struct JwtVerifier {
+    jwks: RwLock<JwkSet>,
+    issuers: Vec<String>,
+    audiences: Vec<String>,
+    algorithms: Vec<Algorithm>,
+    leeway: u64,
+}
+
+impl JwtVerifier {
+    pub fn new(jwks: JwkSet) -> Self {
+        Self {
+            jwks: RwLock::new(jwks),
+            issuers: Vec::new(),
+            audiences: Vec::new(),
+            algorithms: Vec::new(),
+            leeway: 60,
+        }
+    }
+
+    pub fn from_jwks_str(jwks: &str) -> Result<Self, JwtError> {
+        Ok(Self::new(serde_json::from_str(jwks)?))
+    }
+
+    pub fn from_jwks_file(path: impl AsRef<Path>) -> Result<Self, JwtError> {
+        Self::from_jwks_str(&fs::read_to_string(path)?)
+    }
+
+    pub fn issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.issuers.push(issuer.into());
+        self
+    }
+
+    pub fn audience(mut self, audience: impl Into<String>) -> Self {
+        self.audiences.push(audience.into());
+        self
+    }
+
+    /// Algorithms accepted for keys without an `alg`. Without them such keys only accept the
+    /// default algorithm of their key type, e.g. RS256 for RSA keys.
+    pub fn algorithms(mut self, algorithms: impl IntoIterator<Item = Algorithm>) -> Self {
+        self.algorithms = algorithms.into_iter().collect();
+        self
+    }
+
+    pub fn leeway(mut self, seconds: u64) -> Self {
+        self.leeway = seconds;
+        self
+    }
+
+    pub fn set_jwks(&self, jwks: JwkSet) {
+        *self.jwks.write().unwrap_or_else(|err| err.into_inner()) = jwks;
+    }
+
+    pub fn verify<T>(&self, token: &str) -> Result<T, JwtError>
+    where
+        T: DeserializeOwned,
+    {
+        let header = decode_header(token)?;
+        if !self.algorithms.is_empty() && !self.algorithms.contains(&header.alg) {
+            return Err(JwtError::AlgorithmNotAllowed(header.alg));
+        }
+
+        let (key, algorithm) = {
+            let jwks = self.jwks.read().unwrap_or_else(|err| err.into_inner());
+            let jwk = find_key(&jwks, header.kid.as_deref()).ok_or(JwtError::KeyNotFound)?;
+            // The key or the configuration picks the algorithm, never the token alone.
+            let algorithm = match jwk.common.key_algorithm {
+                Some(key_algorithm) => key_algorithm.to_string().parse().ok(),
+                None if !self.algorithms.is_empty() => Some(header.alg),
+                None => default_algorithm(jwk),
+            };
+            if algorithm != Some(header.alg) {
+                return Err(JwtError::AlgorithmNotAllowed(header.alg));
+            }
+            (DecodingKey::from_jwk(jwk)?, header.alg)
+        };
+
+        let mut validation = Validation::new(algorithm);
+        validation.leeway = self.leeway;
+        validation.validate_nbf = true;
+        // jsonwebtoken only checks `iss` and `aud` when the token carries them, so configured
+        // ones must also be required.
+        if !self.issuers.is_empty() {
+            validation.set_issuer(&self.issuers);
+            validation.required_spec_claims.insert("iss".to_owned());
+        }
+        if self.audiences.is_empty() {
+            validation.validate_aud = false;
+        } else {
+            validation.set_audience(&self.audiences);
+            validation.required_spec_claims.insert("aud".to_owned());
+        }
+
+        Ok(decode(token, &key, &validation)?.claims)
+    }
+}
+
+/// An `x-access-token` whose signature and registered claims were checked by a [`JwtVerifier`].
+#[derive(Debug)]
+pub struct VerifiedAccessToken<T = serde_json::Value>
+where
+    T: DeserializeOwned,
+{
+    token: String,
+    claims: T,
+}
+
+impl<T> VerifiedAccessToken<T>
+where
+    T: DeserializeOwned,
+{
+    pub fn verify(verifier: &JwtVerifier, token: impl Into<String>) -> Result<Self, JwtError> {
+        let token = token.into();
+        let claims = verifier.verify(&token)?;
+
+        Ok(VerifiedAccessToken { token, claims })
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn claims(&self) -> &T {
+        &self.claims
+    }
+
+    pub fn into_parts(self) -> (String, T) {
+        (self.token, self.claims)
+    }
+}
+
+impl<T> Deref for VerifiedAccessToken<T>
+where
+    T: DeserializeOwned,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.claims
+    }
+}
+
+fn default_algorithm(jwk: &Jwk) -> Option<Algorithm> {
+    match &jwk.algorithm {
+        AlgorithmParameters::OctetKey(_) => Some(Algorithm::HS256),
+        AlgorithmParameters::RSA(_) => Some(Algorithm::RS256),
+        AlgorithmParameters::EllipticCurve(params) => match params.curve {
+            EllipticCurve::P256 => Some(Algorithm::ES256),
+            EllipticCurve::P384 => Some(Algorithm::ES384),
+            _ => None,
+        },
+        AlgorithmParameters::OctetKeyPair(_) => Some(Algorithm::EdDSA),
+    }
+}
+
+fn find_key<'a>(jwks: &'a JwkSet, kid: Option<&str>) -> Option<&'a Jwk> {
+    match kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::prelude::*;
+    use jsonwebtoken::{encode, get_current_timestamp, EncodingKey, Header};
+    use serde_json::{json, Value};
+
+    const SECRET: &[u8] = b"super-secret-signing-key";
+
+    fn jwks() -> String {
+        json!({
+            "keys": [{
+                "kty": "oct",
+                "kid": "k1",
+                "alg": "HS256",
+                "k": BASE64_URL_SAFE_NO_PAD.encode(SECRET)
+            }]
+        })
+        .to_string()
+    }
+
+    fn sign(kid: &str, claims: Value) -> String {
+        sign_with(Algorithm::HS256, kid, claims)
+    }
+
+    fn sign_with(algorithm: Algorithm, kid: &str, claims: Value) -> String {
+        let mut header = Header::new(algorithm);
+        header.kid = Some(kid.to_owned());
+        encode(&header, &claims, &EncodingKey::from_secret(SECRET)).unwrap()
+    }
+
+    #[test]
+    fn test_verify() {
+        let verifier = JwtVerifier::from_jwks_str(&jwks())
+            .unwrap()
+            .issuer("https://auth.example.com")
+            .audience("orders");
+        let now = get_current_timestamp();
+
+        let token = sign(
+            "k1",
+            json!({"sub": "jane", "iss": "https://auth.example.com", "aud": "orders", "exp": now + 300}),
+        );
+        let claims: Value = verifier.verify(&token).unwrap();
+        assert_eq!(claims["sub"], "jane");
+
+        let expired = sign(
+            "k1",
+            json!({"iss": "https://auth.example.com", "aud": "orders", "exp": now - 3600}),
+        );
+        assert!(matches!(
+            verifier.verify::<Value>(&expired),
+            Err(JwtError::InvalidToken(_))
+        ));
+
+        let not_yet_valid = sign(
+            "k1",
+            json!({"iss": "https://auth.example.com", "aud": "orders", "exp": now + 7200, "nbf": now + 3600}),
+        );
+        assert!(matches!(
+            verifier.verify::<Value>(&not_yet_valid),
+            Err(JwtError::InvalidToken(_))
+        ));
+
+        let wrong_audience = sign(
+            "k1",
+            json!({"iss": "https://auth.example.com", "aud": "billing", "exp": now + 300}),
+        );
+        assert!(matches!(
+            verifier.verify::<Value>(&wrong_audience),
+            Err(JwtError::InvalidToken(_))
+        ));
+
+        let missing_issuer = sign("k1", json!({"aud": "orders", "exp": now + 300}));
+        assert!(matches!(
+            verifier.verify::<Value>(&missing_issuer),
+            Err(JwtError::InvalidToken(_))
+        ));
+
+        let missing_audience = sign(
+            "k1",
+            json!({"iss": "https://auth.example.com", "exp": now + 300}),
+        );
+        assert!(matches!(
+            verifier.verify::<Value>(&missing_audience),
+            Err(JwtError::InvalidToken(_))
+        ));
+
+        let unknown_key = sign("k2", json!({"exp": now + 300}));
+        assert!(matches!(
+            verifier.verify::<Value>(&unknown_key),
+            Err(JwtError::KeyNotFound)
+        ));
+
+        let tampered = format!("{}x", &token[..token.len() - 1]);
+        assert!(matches!(
+            verifier.verify::<Value>(&tampered),
+            Err(JwtError::InvalidToken(_))
+        ));
+    }
+
+    #[test]
+    fn test_algorithm_not_chosen_by_token() {
+        let without_alg = json!({
+            "keys": [{"kty": "oct", "kid": "k1", "k": BASE64_URL_SAFE_NO_PAD.encode(SECRET)}]
+        })
+        .to_string();
+        let claims = json!({"exp": get_current_timestamp() + 300});
+        let hs256 = sign_with(Algorithm::HS256, "k1", claims.clone());
+        let hs384 = sign_with(Algorithm::HS384, "k1", claims);
+
+        let verifier = JwtVerifier::from_jwks_str(&without_alg).unwrap();
+        assert!(verifier.verify::<Value>(&hs256).is_ok());
+        assert!(matches!(
+            verifier.verify::<Value>(&hs384),
+            Err(JwtError::AlgorithmNotAllowed(Algorithm::HS384))
+        ));
+
+        let verifier = verifier.algorithms([Algorithm::HS384]);
+        assert!(verifier.verify::<Value>(&hs384).is_ok());
+        assert!(matches!(
+            verifier.verify::<Value>(&hs256),
+            Err(JwtError::AlgorithmNotAllowed(Algorithm::HS256))
+        ));
+
+        // The key's own `alg` wins over the allow-list.
+        let verifier = JwtVerifier::from_jwks_str(&jwks())
+            .unwrap()
+            .algorithms([Algorithm::HS256, Algorithm::HS384]);
+        assert!(verifier.verify::<Value>(&hs256).is_ok());
+        assert!(matches!(
+            verifier.verify::<Value>(&hs384),
+            Err(JwtError::AlgorithmNotAllowed(Algorithm::HS384))
+        ));
+    }
+
+    #[test]
+    fn test_set_jwks() {
+        let verifier = JwtVerifier::new(JwkSet { keys: Vec::new() });
+        let token = sign("k1", json!({"exp": get_current_timestamp() + 300}));
+        assert!(matches!(
+            verifier.verify::<Value>(&token),
+            Err(JwtError::KeyNotFound)
+        ));
+
+        verifier.set_jwks(serde_json::from_str(&jwks()).unwrap());
+        assert!(verifier.verify::<Value>(&token).is_ok());
+    }
+
+    #[cfg(feature = "actix")]
+    #[actix_rt::test]
+    async fn test_verified_access_token_extractor() {
+        use crate::{VerifiedTokenError, X_ACCESS_TOKEN_HEADER};
+        use actix_web::{test::TestRequest, web, FromRequest, ResponseError};
+
+        let token = sign(
+            "k1",
+            json!({"sub": "jane", "exp": get_current_timestamp() + 300}),
+        );
+        let verifier = web::Data::new(JwtVerifier::from_jwks_str(&jwks()).unwrap());
+
+        let req = TestRequest::default()
+            .app_data(verifier.clone())
+            .insert_header((X_ACCESS_TOKEN_HEADER, token.as_str()))
+            .to_http_request();
+        let verified = VerifiedAccessToken::<Value>::extract(&req).await.unwrap();
+        assert_eq!(verified.token(), token);
+        assert_eq!(verified["sub"], "jane");
+
+        let req = TestRequest::default()
+            .app_data(verifier)
+            .insert_header((X_ACCESS_TOKEN_HEADER, "e30.e30.c2ln"))
+            .to_http_request();
+        let err = VerifiedAccessToken::<Value>::extract(&req)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status_code(), actix_web::http::StatusCode::UNAUTHORIZED);
+
+        let req = TestRequest::default()
+            .insert_header((X_ACCESS_TOKEN_HEADER, token.as_str()))
+            .to_http_request();
+        let err = VerifiedAccessToken::<Value>::extract(&req)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VerifiedTokenError::MissingVerifier));
+    }
+}
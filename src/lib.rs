@@ -1,7 +1,85 @@
+#[cfg(feature = "admin")]
+pub mod admin;
+
+#[cfg(all(feature = "ext-plugin", unix))]
+pub mod ext_plugin;
+
+mod claims;
+mod consumer;
+mod token;
+mod user_info;
+
 #[cfg(feature = "actix")]
 mod actix;
 
+#[cfg(any(feature = "actix", feature = "reqwest"))]
+mod propagate;
+
+#[cfg(feature = "axum")]
+mod axum;
+
+#[cfg(feature = "actix")]
+mod config;
+
+#[cfg(feature = "actix")]
+mod forward_auth;
+
 #[cfg(feature = "actix")]
-pub use actix::XUserInfo;
+mod require_claims;
+
+#[cfg(feature = "jwt")]
+mod jwt;
+
+pub use claims::{Address, Audience, KeycloakAccess, KeycloakClaims, RoleClaims, StandardClaims};
+pub use consumer::{ApisixConsumer, MaybeApisixConsumer};
+pub use token::{decode_jwt_payload, JwtDecodeError, XAccessToken, XIdToken};
+pub use user_info::{
+    decode_user_info, decode_user_info_with, encode_user_info, Base64Alphabet, DecodeError,
+    MaybeXUserInfo, XUserInfo,
+};
+
+#[cfg(feature = "actix")]
+pub use actix::{ApisixConsumerError, XTokenError, XUserInfoError};
+
+#[cfg(feature = "actix")]
+pub use config::{ErrorFormat, XUserInfoConfig};
+
+#[cfg(any(feature = "actix", feature = "reqwest"))]
+pub use propagate::WithUserInfo;
+
+#[cfg(feature = "actix")]
+pub use forward_auth::{ForwardAuthError, ForwardAuthRequest, ForwardAuthResponse};
+
+#[cfg(feature = "actix")]
+pub use require_claims::{RequireClaims, RequireClaimsError, RequireClaimsMiddleware};
+
+#[cfg(all(feature = "actix", feature = "jwt"))]
+pub use actix::VerifiedTokenError;
+
+#[cfg(feature = "jwt")]
+pub use jwt::{JwtError, JwtVerifier, VerifiedAccessToken};
+
+#[cfg(feature = "axum")]
+pub use axum::{ApisixConsumerRejection, XTokenRejection, XUserInfoRejection};
+
+pub const X_USER_INFO_HEADER: &str = "x-userinfo";
+
+pub const X_ACCESS_TOKEN_HEADER: &str = "x-access-token";
+
+pub const X_ID_TOKEN_HEADER: &str = "x-id-token";
+
+pub const X_CONSUMER_USERNAME_HEADER: &str = "x-consumer-username";
+
+pub const X_CREDENTIAL_IDENTIFIER_HEADER: &str = "x-credential-identifier";
+
+pub const X_CONSUMER_CUSTOM_ID_HEADER: &str = "x-consumer-custom-id";
+
+pub const X_FORWARDED_METHOD_HEADER: &str = "x-forwarded-method";
+
+pub const X_FORWARDED_PROTO_HEADER: &str = "x-forwarded-proto";
+
+pub const X_FORWARDED_HOST_HEADER: &str = "x-forwarded-host";
+
+pub const X_FORWARDED_URI_HEADER: &str = "x-forwarded-uri";
 
-const X_USER_INFO_HEADER: &str = "x-userinfo";
+pub const X_FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
@@ -0,0 +1,525 @@
+use std::{fs, path::PathBuf, process::ExitCode};
+
+use apisix_rs::{
+    admin::{
+        sync::{self, Change, DesiredState, SyncOptions},
+        AdminClient, Item, Resource, ResourceKind, Route, SchemaValidator, StandaloneConfig,
+        Violation,
+    },
+    encode_user_info, XUserInfo,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+type Error = Box<dyn std::error::Error>;
+
+#[derive(Parser)]
+#[command(
+    name = "apisix-rs",
+    version,
+    about = "Debug APISIX headers and manage routes"
+)]
+struct Cli {
+    #[arg(short, long, value_enum, default_value_t = Format::Table, global = true)]
+    output: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+    Yaml,
+}
+
+#[derive(Args)]
+struct AdminArgs {
+    #[arg(
+        long,
+        env = "APISIX_ADMIN_URL",
+        default_value = "http://127.0.0.1:9180"
+    )]
+    admin_url: String,
+
+    #[arg(long, env = "APISIX_ADMIN_KEY", hide_env_values = true)]
+    api_key: String,
+}
+
+impl AdminArgs {
+    fn client(&self) -> AdminClient {
+        AdminClient::new(&self.admin_url, &self.api_key)
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Decode an x-userinfo header value
+    DecodeUserinfo { value: String },
+
+    /// Encode a JSON object as an x-userinfo header value
+    EncodeUserinfo { json: String },
+
+    /// Manage routes through the Admin API
+    Routes {
+        #[command(subcommand)]
+        command: RoutesCommand,
+    },
+
+    /// Show the changes that would make the gateway match a standalone config
+    Diff {
+        #[arg(short, long)]
+        file: PathBuf,
+
+        /// Also list resources that are not in the file for deletion
+        #[arg(long)]
+        prune: bool,
+
+        #[command(flatten)]
+        admin: AdminArgs,
+    },
+
+    /// Validate a standalone config against a Control API `/v1/schema` dump
+    Validate {
+        #[arg(short, long)]
+        file: PathBuf,
+
+        #[arg(short, long)]
+        schema: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum RoutesCommand {
+    List {
+        #[command(flatten)]
+        admin: AdminArgs,
+    },
+
+    Get {
+        id: String,
+
+        #[command(flatten)]
+        admin: AdminArgs,
+    },
+
+    /// Create or replace the route defined in a YAML or JSON file
+    Apply {
+        #[arg(short, long)]
+        file: PathBuf,
+
+        #[command(flatten)]
+        admin: AdminArgs,
+    },
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command, cli.output).await {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(command: Command, format: Format) -> Result<ExitCode, Error> {
+    match command {
+        Command::DecodeUserinfo { value } => {
+            let user_info = XUserInfo::<Value>::decode(value.trim())?.into_inner();
+            let rows = match &user_info {
+                Value::Object(fields) => fields
+                    .iter()
+                    .map(|(key, value)| vec![key.clone(), display(value)])
+                    .collect(),
+                value => vec![vec![String::new(), display(value)]],
+            };
+            print(format, &user_info, &["CLAIM", "VALUE"], rows)?;
+        }
+        Command::EncodeUserinfo { json } => {
+            let value: Value = serde_json::from_str(&json)?;
+            println!("{}", encode_user_info(&value)?);
+        }
+        Command::Routes { command } => routes(command, format).await?,
+        Command::Diff { file, prune, admin } => {
+            let config = read_config(&file)?;
+            let desired = DesiredState::from_standalone(&config)?;
+            let options = SyncOptions {
+                prune,
+                dry_run: true,
+                ..Default::default()
+            };
+            let plan = sync::plan(&admin.client(), &desired, options).await?;
+
+            let rows = plan.changes.iter().map(change_row).collect();
+            let changes: Vec<_> = plan.changes.iter().map(change_json).collect();
+            print(format, &changes, &["ACTION", "KIND", "ID", "FIELDS"], rows)?;
+        }
+        Command::Validate { file, schema } => {
+            let config = read_config(&file)?;
+            let validator = SchemaValidator::from_file(schema)?;
+
+            let mut violations = Vec::new();
+            check(&validator, "routes", &config.routes, &mut violations);
+            check(&validator, "services", &config.services, &mut violations);
+            check(&validator, "upstreams", &config.upstreams, &mut violations);
+            check(
+                &validator,
+                "stream_routes",
+                &config.stream_routes,
+                &mut violations,
+            );
+            check(&validator, "consumers", &config.consumers, &mut violations);
+            check(
+                &validator,
+                "consumer_groups",
+                &config.consumer_groups,
+                &mut violations,
+            );
+            check(&validator, "ssls", &config.ssls, &mut violations);
+            check(
+                &validator,
+                "global_rules",
+                &config.global_rules,
+                &mut violations,
+            );
+            check(
+                &validator,
+                "plugin_configs",
+                &config.plugin_configs,
+                &mut violations,
+            );
+
+            let rows = violations
+                .iter()
+                .map(|v| vec![v.pointer.clone(), v.message.clone()])
+                .collect();
+            let output: Vec<_> = violations
+                .iter()
+                .map(|v| json!({"pointer": v.pointer, "message": v.message}))
+                .collect();
+            print(format, &output, &["POINTER", "MESSAGE"], rows)?;
+
+            if !violations.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn routes(command: RoutesCommand, format: Format) -> Result<(), Error> {
+    let routes = match command {
+        RoutesCommand::List { admin } => admin
+            .client()
+            .routes()
+            .list_all()
+            .await?
+            .into_iter()
+            .map(with_id)
+            .collect(),
+        RoutesCommand::Get { id, admin } => {
+            let item = admin.client().routes().get(&id).await?;
+            vec![with_id(item)]
+        }
+        RoutesCommand::Apply { file, admin } => {
+            let route: Route = serde_yaml::from_str(&fs::read_to_string(&file)?)?;
+            let id = route
+                .id
+                .clone()
+                .ok_or_else(|| format!("{} has no route id", file.display()))?;
+            let item = admin.client().routes().put(&id, &route).await?;
+            vec![with_id(item)]
+        }
+    };
+
+    let rows = routes.iter().map(route_row).collect();
+    print(
+        format,
+        &routes,
+        &["ID", "NAME", "URI", "METHODS", "UPSTREAM"],
+        rows,
+    )
+}
+
+fn read_config(file: &PathBuf) -> Result<StandaloneConfig, Error> {
+    let input = fs::read_to_string(file)?;
+    let config = match file.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => StandaloneConfig::from_json(&input)?,
+        _ => StandaloneConfig::from_yaml(&input)?,
+    };
+    Ok(config)
+}
+
+fn check<R>(validator: &SchemaValidator, section: &str, resources: &[R], out: &mut Vec<Violation>)
+where
+    R: Resource,
+{
+    for (i, resource) in resources.iter().enumerate() {
+        if let Err(errors) = validator.validate(resource) {
+            out.extend(errors.0.into_iter().map(|violation| Violation {
+                pointer: format!("/{section}/{i}{}", violation.pointer),
+                message: violation.message,
+            }));
+        }
+    }
+}
+
+fn with_id(item: Item<Route>) -> Route {
+    let id = item.id().to_owned();
+    let mut route = item.value;
+    route.id.get_or_insert(id);
+    route
+}
+
+fn route_row(route: &Route) -> Vec<String> {
+    let uri = route
+        .uri
+        .clone()
+        .or_else(|| route.uris.as_ref().map(|uris| uris.join(",")))
+        .unwrap_or_default();
+    let upstream = route
+        .upstream_id
+        .clone()
+        .or_else(|| route.service_id.as_ref().map(|id| format!("service:{id}")))
+        .or_else(|| route.upstream.as_ref().map(|_| "inline".to_owned()))
+        .unwrap_or_default();
+
+    vec![
+        route.id.clone().unwrap_or_default(),
+        route.name.clone().unwrap_or_default(),
+        uri,
+        route
+            .methods
+            .as_ref()
+            .map(|m| m.join(","))
+            .unwrap_or_default(),
+        upstream,
+    ]
+}
+
+fn change_row(change: &Change) -> Vec<String> {
+    let (action, fields) = match change {
+        Change::Create { .. } => ("create", String::new()),
+        Change::Update { diffs, .. } => (
+            "update",
+            diffs
+                .iter()
+                .map(|diff| diff.pointer.as_str())
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        Change::Delete { .. } => ("delete", String::new()),
+    };
+    let key = change.key();
+    vec![
+        action.to_owned(),
+        key.kind.schema_name().to_owned(),
+        key.id.clone(),
+        fields,
+    ]
+}
+
+fn change_json(change: &Change) -> Value {
+    let key = change.key();
+    let mut output = json!({"kind": key.kind.schema_name(), "id": key.id});
+    match change {
+        Change::Create { value, .. } => {
+            output["action"] = json!("create");
+            output["value"] = redact(key.kind, value);
+        }
+        Change::Update { value, diffs, .. } => {
+            output["action"] = json!("update");
+            output["value"] = redact(key.kind, value);
+            output["diffs"] = diffs
+                .iter()
+                .map(|diff| json!({"pointer": diff.pointer, "old": diff.old, "new": diff.new}))
+                .collect();
+        }
+        Change::Delete { .. } => output["action"] = json!("delete"),
+    }
+    output
+}
+
+/// Hides SSL private keys from the printed plan.
+fn redact(kind: ResourceKind, value: &Value) -> Value {
+    let mut value = value.clone();
+    if let (ResourceKind::Ssl, Some(object)) = (kind, value.as_object_mut()) {
+        for field in ["key", "keys"] {
+            if let Some(secret) = object.get_mut(field) {
+                *secret = json!("<redacted>");
+            }
+        }
+    }
+    value
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+fn print<T>(
+    format: Format,
+    value: &T,
+    headers: &[&str],
+    rows: Vec<Vec<String>>,
+) -> Result<(), Error>
+where
+    T: Serialize,
+{
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        Format::Yaml => print!("{}", serde_yaml::to_string(value)?),
+        Format::Table => {
+            let mut widths: Vec<_> = headers.iter().map(|header| header.len()).collect();
+            for row in &rows {
+                for (width, cell) in widths.iter_mut().zip(row) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+
+            let header: Vec<_> = headers.iter().map(|header| header.to_string()).collect();
+            for row in std::iter::once(&header).chain(&rows) {
+                let line = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{cell:width$}"))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                println!("{}", line.trim_end());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use apisix_rs::admin::{
+        sync::{FieldDiff, ResourceKey},
+        ResourceKind,
+    };
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("apisix-rs-cli-{}-{name}", std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_config() {
+        let yaml = temp_file(
+            "config.yaml",
+            "routes:\n  - id: orders\n    uri: /orders\n#END\n",
+        );
+        let json = temp_file(
+            "config.json",
+            r#"{"routes": [{"id": "orders", "uri": "/orders"}]}"#,
+        );
+
+        for path in [yaml, json] {
+            let config = read_config(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+            assert_eq!(config.routes.len(), 1);
+            assert_eq!(config.routes[0].uri.as_deref(), Some("/orders"));
+        }
+
+        let missing_end = temp_file("missing-end.yaml", "routes: []\n");
+        assert!(read_config(&missing_end).is_err());
+        fs::remove_file(&missing_end).unwrap();
+    }
+
+    #[test]
+    fn test_check_prefixes_pointers() {
+        let validator = SchemaValidator::from_value(&json!({
+            "main": {
+                "route": {
+                    "type": "object",
+                    "properties": {"uri": {"type": "string", "minLength": 1}}
+                }
+            }
+        }))
+        .unwrap();
+        let routes = [
+            Route {
+                uri: Some("/orders".into()),
+                ..Default::default()
+            },
+            Route {
+                uri: Some(String::new()),
+                ..Default::default()
+            },
+        ];
+
+        let mut violations = Vec::new();
+        check(&validator, "routes", &routes, &mut violations);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].pointer, "/routes/1/uri");
+    }
+
+    #[test]
+    fn test_route_row() {
+        let route = Route {
+            id: Some("orders".into()),
+            name: Some("Orders".into()),
+            uris: Some(vec!["/orders".into(), "/orders/*".into()]),
+            methods: Some(vec!["GET".into(), "POST".into()]),
+            service_id: Some("shop".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            route_row(&route),
+            [
+                "orders",
+                "Orders",
+                "/orders,/orders/*",
+                "GET,POST",
+                "service:shop"
+            ]
+        );
+
+        assert_eq!(route_row(&Route::default()), ["", "", "", "", ""]);
+    }
+
+    #[test]
+    fn test_change_row() {
+        let key = ResourceKey {
+            kind: ResourceKind::Route,
+            id: "orders".into(),
+        };
+        let update = Change::Update {
+            key: key.clone(),
+            value: json!({}),
+            diffs: vec![
+                FieldDiff {
+                    pointer: "/uri".into(),
+                    old: Some(json!("/orders")),
+                    new: Some(json!("/orders/*")),
+                },
+                FieldDiff {
+                    pointer: "/hosts".into(),
+                    old: Some(json!(["api.example.com"])),
+                    new: None,
+                },
+            ],
+        };
+        assert_eq!(
+            change_row(&update),
+            ["update", "route", "orders", "/uri,/hosts"]
+        );
+        assert_eq!(
+            change_row(&Change::Delete { key }),
+            ["delete", "route", "orders", ""]
+        );
+    }
+}
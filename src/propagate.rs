@@ -0,0 +1,115 @@
+use serde::Serialize;
+
+use crate::{encode_user_info, X_USER_INFO_HEADER};
+
+/// Attaches an `x-userinfo` header to an outgoing or test request.
+///
+/// Used to propagate the current user to internal services, which decode it with
+/// [`XUserInfo`](crate::XUserInfo) as if the request had come through APISIX:
+///
+/// ```ignore
+/// async fn orders(user: XUserInfo<Claims>, client: web::Data<reqwest::Client>) -> impl Responder {
+///     client.get("http://billing/invoices").with_userinfo(&*user).send().await
+///     // ...
+/// }
+/// ```
+///
+/// # Panics
+///
+/// Panics if `value` cannot be serialized to JSON, like [`XUserInfo::encode`](crate::XUserInfo).
+pub trait WithUserInfo: Sized {
+    fn with_userinfo<T>(self, value: &T) -> Self
+    where
+        T: Serialize + ?Sized;
+}
+
+fn encode<T>(value: &T) -> String
+where
+    T: Serialize + ?Sized,
+{
+    encode_user_info(value).expect("x-userinfo must serialize to json")
+}
+
+#[cfg(feature = "actix")]
+impl WithUserInfo for actix_web::test::TestRequest {
+    fn with_userinfo<T>(self, value: &T) -> Self
+    where
+        T: Serialize + ?Sized,
+    {
+        self.insert_header((X_USER_INFO_HEADER, encode(value)))
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl WithUserInfo for reqwest::RequestBuilder {
+    fn with_userinfo<T>(self, value: &T) -> Self
+    where
+        T: Serialize + ?Sized,
+    {
+        self.header(X_USER_INFO_HEADER, encode(value))
+    }
+}
+
+#[cfg(feature = "awc")]
+impl WithUserInfo for awc::ClientRequest {
+    fn with_userinfo<T>(self, value: &T) -> Self
+    where
+        T: Serialize + ?Sized,
+    {
+        self.insert_header((X_USER_INFO_HEADER, encode(value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::XUserInfo;
+    #[cfg(feature = "actix")]
+    use actix_web::{test::TestRequest, FromRequest};
+    use serde_json::{json, Value};
+
+    #[cfg(feature = "actix")]
+    #[actix_rt::test]
+    async fn test_test_request() {
+        let claims = json!({"sub": "user-1", "roles": ["admin"]});
+        let req = TestRequest::default()
+            .with_userinfo(&claims)
+            .to_http_request();
+
+        let user_info = XUserInfo::<Value>::extract(&req).await.unwrap();
+        assert_eq!(*user_info, claims);
+        assert_eq!(
+            req.headers().get(X_USER_INFO_HEADER).unwrap(),
+            XUserInfo::encode(&claims)
+        );
+    }
+
+    #[cfg(feature = "reqwest")]
+    #[test]
+    fn test_reqwest() {
+        let user_info = XUserInfo(json!({"sub": "user-1"}));
+        let req = reqwest::Client::new()
+            .get("http://billing.internal/invoices")
+            .with_userinfo(&*user_info)
+            .build()
+            .unwrap();
+
+        let header = req.headers().get(X_USER_INFO_HEADER).unwrap();
+        let decoded = XUserInfo::<Value>::decode(header.as_bytes()).unwrap();
+        assert_eq!(*decoded, *user_info);
+    }
+
+    #[cfg(feature = "awc")]
+    #[actix_rt::test]
+    async fn test_awc() {
+        let claims = json!({"sub": "user-1"});
+        let req = awc::Client::new()
+            .get("http://billing.internal/invoices")
+            .with_userinfo(&claims);
+
+        assert_eq!(
+            req.headers().get(X_USER_INFO_HEADER).unwrap(),
+            XUserInfo::encode(&claims)
+        );
+    }
+}
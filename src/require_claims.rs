@@ -0,0 +1,352 @@
+use std::{
+    future::{ready, Future, Ready},
+    marker::PhantomData,
+    pin::Pin,
+    rc::Rc,
+};
+
+use actix_web::{
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header::ContentType, StatusCode},
+    HttpMessage, HttpResponse, ResponseError,
+};
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::{RoleClaims, XUserInfo, XUserInfoConfig, XUserInfoError};
+
+type Predicate<T> = Rc<dyn Fn(&T) -> bool>;
+
+#[derive(Error, Debug)]
+pub enum RequireClaimsError {
+    #[error(transparent)]
+    UserInfo(#[from] XUserInfoError),
+
+    #[error("insufficient claims")]
+    InsufficientClaims,
+}
+
+impl ResponseError for RequireClaimsError {
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(ContentType::plaintext())
+            .body(self.to_string())
+    }
+
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RequireClaimsError::UserInfo(XUserInfoError::MissingHeader) => StatusCode::UNAUTHORIZED,
+            RequireClaimsError::UserInfo(_) => StatusCode::BAD_REQUEST,
+            RequireClaimsError::InsufficientClaims => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+/// Middleware that decodes the `x-userinfo` header once and checks it against a predicate.
+///
+/// Rejects a missing header with 401, an invalid one with 400 and a failing predicate with 403;
+/// statuses and format can be changed through [`XUserInfoConfig`]. On success the decoded value
+/// is stored in the request extensions, so a following `XUserInfo<T>` extraction does not decode
+/// the header again.
+pub struct RequireClaims<T> {
+    predicate: Predicate<T>,
+}
+
+impl<T> Clone for RequireClaims<T> {
+    fn clone(&self) -> Self {
+        Self {
+            predicate: self.predicate.clone(),
+        }
+    }
+}
+
+impl<T> RequireClaims<T>
+where
+    T: DeserializeOwned + 'static,
+{
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&T) -> bool + 'static,
+    {
+        Self {
+            predicate: Rc::new(predicate),
+        }
+    }
+
+    pub fn authenticated() -> Self {
+        Self::new(|_| true)
+    }
+
+    pub fn and(self, other: Self) -> Self {
+        Self::new(move |claims| (self.predicate)(claims) && (other.predicate)(claims))
+    }
+
+    pub fn or(self, other: Self) -> Self {
+        Self::new(move |claims| (self.predicate)(claims) || (other.predicate)(claims))
+    }
+}
+
+impl<T> RequireClaims<T>
+where
+    T: RoleClaims + DeserializeOwned + 'static,
+{
+    pub fn any_role<I, R>(roles: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<String>,
+    {
+        let roles = collect(roles);
+        Self::new(move |claims: &T| roles.iter().any(|role| claims.has_role(role)))
+    }
+
+    pub fn all_roles<I, R>(roles: I) -> Self
+    where
+        I: IntoIterator<Item = R>,
+        R: Into<String>,
+    {
+        let roles = collect(roles);
+        Self::new(move |claims: &T| roles.iter().all(|role| claims.has_role(role)))
+    }
+
+    pub fn any_scope<I, S>(scopes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let scopes = collect(scopes);
+        Self::new(move |claims: &T| scopes.iter().any(|scope| claims.has_scope(scope)))
+    }
+
+    pub fn all_scopes<I, S>(scopes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let scopes = collect(scopes);
+        Self::new(move |claims: &T| scopes.iter().all(|scope| claims.has_scope(scope)))
+    }
+}
+
+fn collect<I, S>(values: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    values.into_iter().map(Into::into).collect()
+}
+
+impl<S, B, T> Transform<S, ServiceRequest> for RequireClaims<T>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+    T: DeserializeOwned + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = RequireClaimsMiddleware<S, T>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequireClaimsMiddleware {
+            service,
+            predicate: self.predicate.clone(),
+            _claims: PhantomData,
+        }))
+    }
+}
+
+pub struct RequireClaimsMiddleware<S, T> {
+    service: S,
+    predicate: Predicate<T>,
+    _claims: PhantomData<T>,
+}
+
+impl<S, T> RequireClaimsMiddleware<S, T>
+where
+    T: DeserializeOwned,
+{
+    fn authorize(&self, req: &ServiceRequest) -> Result<XUserInfo<T>, RequireClaimsError> {
+        let user_info = XUserInfo::<T>::try_from(req.request())?;
+
+        if (self.predicate)(&user_info) {
+            Ok(user_info)
+        } else {
+            Err(RequireClaimsError::InsufficientClaims)
+        }
+    }
+}
+
+impl<S, B, T> Service<ServiceRequest> for RequireClaimsMiddleware<S, T>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>,
+    S::Future: 'static,
+    B: 'static,
+    T: DeserializeOwned + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        match self.authorize(&req) {
+            Ok(user_info) => {
+                req.extensions_mut().insert(user_info);
+                Box::pin(self.service.call(req))
+            }
+            Err(err) => {
+                let err = XUserInfoConfig::from_req(req.request())
+                    .require_claims_error(err, req.request());
+                Box::pin(ready(Err(err)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorFormat, KeycloakClaims, X_USER_INFO_HEADER};
+    use actix_web::{body::to_bytes, http::StatusCode, test, web, App, HttpResponse};
+    use base64::prelude::*;
+    use serde_json::json;
+
+    async fn handler(user_info: XUserInfo<KeycloakClaims>) -> HttpResponse {
+        HttpResponse::Ok().body(user_info.sub.clone())
+    }
+
+    fn header(roles: &[&str], scope: &str) -> (&'static str, String) {
+        let claims = json!({
+            "sub": "jane",
+            "realm_access": {"roles": roles},
+            "scope": scope
+        });
+        (
+            X_USER_INFO_HEADER,
+            BASE64_STANDARD.encode(claims.to_string()),
+        )
+    }
+
+    #[actix_rt::test]
+    async fn test_require_claims() {
+        let app = test::init_service(
+            App::new()
+                .wrap(
+                    RequireClaims::<KeycloakClaims>::any_role(["admin", "ops"])
+                        .and(RequireClaims::all_scopes(["orders:write"])),
+                )
+                .route("/", web::get().to(handler)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((X_USER_INFO_HEADER, "%%%"))
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::BAD_REQUEST
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header(header(&["user"], "orders:write"))
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(err.as_response_error().status_code(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header(header(&["ops"], "openid orders:read"))
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(err.as_response_error().status_code(), StatusCode::FORBIDDEN);
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header(header(&["ops"], "openid orders:write"))
+            .to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        assert_eq!(body, "jane");
+    }
+
+    #[actix_rt::test]
+    async fn test_require_claims_stores_user_info() {
+        let app = test::init_service(
+            App::new()
+                .wrap(RequireClaims::<KeycloakClaims>::new(|claims| {
+                    claims.in_group("/staff")
+                }))
+                .route(
+                    "/",
+                    web::get().to(|req: actix_web::HttpRequest| async move {
+                        let stored = req.extensions().contains::<XUserInfo<KeycloakClaims>>();
+                        HttpResponse::Ok().body(stored.to_string())
+                    }),
+                ),
+        )
+        .await;
+
+        let claims = json!({"sub": "jane", "groups": ["/staff"]});
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((
+                X_USER_INFO_HEADER,
+                BASE64_STANDARD.encode(claims.to_string()),
+            ))
+            .to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        assert_eq!(body, "true");
+    }
+
+    #[actix_rt::test]
+    async fn test_require_claims_uses_config() {
+        let app = test::init_service(
+            App::new()
+                .app_data(
+                    XUserInfoConfig::default()
+                        .missing_header_status(StatusCode::BAD_REQUEST)
+                        .insufficient_claims_status(StatusCode::NOT_FOUND)
+                        .error_format(ErrorFormat::ProblemJson),
+                )
+                .wrap(RequireClaims::<KeycloakClaims>::any_role(["admin"]))
+                .route("/", web::get().to(handler)),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/").to_request();
+        let response = test::try_call_service(&app, req)
+            .await
+            .unwrap_err()
+            .error_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/problem+json"
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header(header(&["user"], ""))
+            .to_request();
+        let response = test::try_call_service(&app, req)
+            .await
+            .unwrap_err()
+            .error_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body()).await.unwrap()).unwrap();
+        assert_eq!(body["detail"], "insufficient claims");
+    }
+}
@@ -0,0 +1,151 @@
+use std::ops::Deref;
+
+use base64::prelude::*;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::{decode_user_info, DecodeError};
+
+#[derive(Error, Debug)]
+pub enum JwtDecodeError {
+    #[error("malformed jwt, expected header.payload.signature")]
+    Malformed,
+
+    #[error("base64 decode error: {0}")]
+    Base64(#[from] base64::DecodeError),
+
+    #[error("json decode error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Decodes the payload of a JWT without verifying its signature.
+pub fn decode_jwt_payload<T>(token: &str) -> Result<T, JwtDecodeError>
+where
+    T: DeserializeOwned,
+{
+    let mut segments = token.split('.');
+    let payload = match (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) {
+        (Some(_), Some(payload), Some(_), None) => payload,
+        _ => return Err(JwtDecodeError::Malformed),
+    };
+
+    let base64_decoded = BASE64_URL_SAFE_NO_PAD.decode(payload.trim_end_matches('='))?;
+
+    Ok(serde_json::from_slice(&base64_decoded)?)
+}
+
+/// The `x-id-token` header, base64 encoded JSON like `x-userinfo`.
+#[derive(Debug)]
+pub struct XIdToken<T>(pub(crate) T)
+where
+    T: DeserializeOwned;
+
+impl<T> XIdToken<T>
+where
+    T: DeserializeOwned,
+{
+    pub fn decode(input: impl AsRef<[u8]>) -> Result<Self, DecodeError> {
+        decode_user_info(input).map(XIdToken)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for XIdToken<T>
+where
+    T: DeserializeOwned,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The raw `x-access-token` JWT together with its unverified payload.
+#[derive(Debug)]
+pub struct XAccessToken<T = serde_json::Value>
+where
+    T: DeserializeOwned,
+{
+    token: String,
+    claims: T,
+}
+
+impl<T> XAccessToken<T>
+where
+    T: DeserializeOwned,
+{
+    pub fn decode(token: impl Into<String>) -> Result<Self, JwtDecodeError> {
+        let token = token.into();
+        let claims = decode_jwt_payload(&token)?;
+
+        Ok(XAccessToken { token, claims })
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub fn claims(&self) -> &T {
+        &self.claims
+    }
+
+    pub fn into_parts(self) -> (String, T) {
+        (self.token, self.claims)
+    }
+}
+
+impl<T> Deref for XAccessToken<T>
+where
+    T: DeserializeOwned,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.claims
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StandardClaims;
+    use serde_json::json;
+
+    #[test]
+    fn test_x_access_token() {
+        let payload =
+            BASE64_URL_SAFE_NO_PAD.encode(json!({"sub": "jane", "scope": "openid"}).to_string());
+        let raw = format!("eyJhbGciOiJSUzI1NiJ9.{payload}.c2lnbmF0dXJl");
+
+        let token = XAccessToken::<StandardClaims>::decode(raw.clone()).unwrap();
+        assert_eq!(token.token(), raw);
+        assert_eq!(token.sub, "jane");
+        assert_eq!(token.extra["scope"], "openid");
+
+        assert!(matches!(
+            XAccessToken::<StandardClaims>::decode("not-a-jwt"),
+            Err(JwtDecodeError::Malformed)
+        ));
+        assert!(matches!(
+            XAccessToken::<StandardClaims>::decode("a.b.c.d"),
+            Err(JwtDecodeError::Malformed)
+        ));
+    }
+
+    #[test]
+    fn test_x_id_token() {
+        let header = BASE64_STANDARD.encode(json!({"sub": "jane", "nonce": "n-0S6"}).to_string());
+
+        let id_token = XIdToken::<StandardClaims>::decode(header).unwrap();
+        assert_eq!(id_token.nonce.as_deref(), Some("n-0S6"));
+    }
+}
@@ -0,0 +1,210 @@
+use std::ops::Deref;
+
+use base64::prelude::*;
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error("base64 decode error: {0}")]
+    Base64(#[from] base64::DecodeError),
+
+    #[error("json decode error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base64Alphabet {
+    Standard,
+    StandardNoPad,
+    UrlSafe,
+    UrlSafeNoPad,
+}
+
+impl Base64Alphabet {
+    fn decode(self, input: &[u8]) -> Result<Vec<u8>, base64::DecodeError> {
+        match self {
+            Base64Alphabet::Standard => BASE64_STANDARD.decode(input),
+            Base64Alphabet::StandardNoPad => BASE64_STANDARD_NO_PAD.decode(input),
+            Base64Alphabet::UrlSafe => BASE64_URL_SAFE.decode(input),
+            Base64Alphabet::UrlSafeNoPad => BASE64_URL_SAFE_NO_PAD.decode(input),
+        }
+    }
+}
+
+/// Decodes an `x-userinfo` header value (base64 encoded JSON) into `T`.
+pub fn decode_user_info<T>(input: impl AsRef<[u8]>) -> Result<T, DecodeError>
+where
+    T: DeserializeOwned,
+{
+    decode_user_info_with(input, &[Base64Alphabet::Standard])
+}
+
+/// Like [`decode_user_info`], trying each base64 alphabet in order until one decodes.
+pub fn decode_user_info_with<T>(
+    input: impl AsRef<[u8]>,
+    alphabets: &[Base64Alphabet],
+) -> Result<T, DecodeError>
+where
+    T: DeserializeOwned,
+{
+    let input = input.as_ref();
+    let mut last_error = base64::DecodeError::InvalidLength(input.len());
+    for alphabet in alphabets {
+        match alphabet.decode(input) {
+            Ok(base64_decoded) => return Ok(serde_json::from_slice(&base64_decoded)?),
+            Err(err) => last_error = err,
+        }
+    }
+
+    Err(last_error.into())
+}
+
+/// Encodes `value` the way APISIX sends `x-userinfo`: standard base64 of its JSON.
+pub fn encode_user_info<T>(value: &T) -> Result<String, serde_json::Error>
+where
+    T: Serialize + ?Sized,
+{
+    Ok(BASE64_STANDARD.encode(serde_json::to_vec(value)?))
+}
+
+#[derive(Debug)]
+pub struct XUserInfo<T>(pub(crate) T)
+where
+    T: DeserializeOwned;
+
+impl<T> XUserInfo<T>
+where
+    T: DeserializeOwned,
+{
+    pub fn decode(input: impl AsRef<[u8]>) -> Result<Self, DecodeError> {
+        decode_user_info(input).map(XUserInfo)
+    }
+
+    pub fn decode_with(
+        input: impl AsRef<[u8]>,
+        alphabets: &[Base64Alphabet],
+    ) -> Result<Self, DecodeError> {
+        decode_user_info_with(input, alphabets).map(XUserInfo)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for XUserInfo<T>
+where
+    T: DeserializeOwned,
+{
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Like [`XUserInfo`], but yields `None` when the header is absent.
+///
+/// A header that is present but cannot be decoded is still rejected.
+#[derive(Debug)]
+pub struct MaybeXUserInfo<T>(pub(crate) Option<T>)
+where
+    T: DeserializeOwned;
+
+impl<T> MaybeXUserInfo<T>
+where
+    T: DeserializeOwned,
+{
+    pub fn into_inner(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for MaybeXUserInfo<T>
+where
+    T: DeserializeOwned,
+{
+    type Target = Option<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> From<Option<XUserInfo<T>>> for MaybeXUserInfo<T>
+where
+    T: DeserializeOwned,
+{
+    fn from(user_info: Option<XUserInfo<T>>) -> Self {
+        MaybeXUserInfo(user_info.map(XUserInfo::into_inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize, Debug)]
+    struct CustomXUserInfo {
+        sub: String,
+        iat: u64,
+    }
+
+    #[test]
+    fn test_decode_user_info() {
+        let encoded =
+            BASE64_STANDARD.encode(json!({"sub": "test sub", "iat": 1516239022}).to_string());
+
+        let from_str: CustomXUserInfo = decode_user_info(&encoded).unwrap();
+        assert_eq!(from_str.sub, "test sub");
+        assert_eq!(from_str.iat, 1516239022);
+
+        let from_bytes = XUserInfo::<CustomXUserInfo>::decode(encoded.as_bytes()).unwrap();
+        assert_eq!(from_bytes.sub, "test sub");
+    }
+
+    #[test]
+    fn test_encode_user_info() {
+        let encoded = encode_user_info(&json!({"sub": "test sub", "iat": 1516239022})).unwrap();
+
+        let decoded = XUserInfo::<CustomXUserInfo>::decode(&encoded).unwrap();
+        assert_eq!(decoded.sub, "test sub");
+        assert_eq!(decoded.iat, 1516239022);
+    }
+
+    #[test]
+    fn test_decode_user_info_errors() {
+        assert!(matches!(
+            decode_user_info::<CustomXUserInfo>("not base64!"),
+            Err(DecodeError::Base64(_))
+        ));
+
+        let encoded = BASE64_STANDARD.encode("{\"sub\": 1}");
+        assert!(matches!(
+            decode_user_info::<CustomXUserInfo>(encoded),
+            Err(DecodeError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_user_info_with_alphabets() {
+        let raw = json!({"sub": "??>>", "iat": 1}).to_string();
+        let url_safe = BASE64_URL_SAFE_NO_PAD.encode(&raw);
+
+        assert!(decode_user_info::<CustomXUserInfo>(&url_safe).is_err());
+
+        let alphabets = [Base64Alphabet::Standard, Base64Alphabet::UrlSafeNoPad];
+        let decoded: CustomXUserInfo = decode_user_info_with(&url_safe, &alphabets).unwrap();
+        assert_eq!(decoded.sub, "??>>");
+
+        let standard = BASE64_STANDARD.encode(&raw);
+        assert!(decode_user_info_with::<CustomXUserInfo>(&standard, &alphabets).is_ok());
+        assert!(matches!(
+            decode_user_info_with::<CustomXUserInfo>(&standard, &[]),
+            Err(DecodeError::Base64(_))
+        ));
+    }
+}